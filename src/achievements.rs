@@ -0,0 +1,352 @@
+use crate::game::GameState;
+use crate::profile::Profile;
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Style},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+    Terminal,
+};
+use std::io;
+
+/// 连续答对成就需要的次数
+const COMBO_TARGET: usize = 100;
+/// 连续打卡成就需要的天数
+const STREAK_TARGET: usize = 30;
+/// 计算平均反应时间需要的最少作答次数
+const LIGHTNING_SAMPLES: usize = 20;
+/// 闪电手成就的平均反应时间上限(毫秒)
+const LIGHTNING_MS: u64 = 1000;
+
+/// 成就，达成记录保存在用户档案中
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Achievement {
+    FirstPass,   // 第一次完成一个方案的全部练习
+    Combo,       // 连续答对100次
+    KeyMastered, // 练完某个键位上的所有字根
+    Streak,      // 连续30天完成每日目标
+    Lightning,   // 最近作答平均反应时间低于1秒
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 5] = [
+        Achievement::FirstPass,
+        Achievement::Combo,
+        Achievement::KeyMastered,
+        Achievement::Streak,
+        Achievement::Lightning,
+    ];
+
+    /// 保存在档案中的标识
+    pub fn id(self) -> &'static str {
+        match self {
+            Achievement::FirstPass => "first-pass",
+            Achievement::Combo => "combo-100",
+            Achievement::KeyMastered => "key-mastered",
+            Achievement::Streak => "streak-30",
+            Achievement::Lightning => "lightning",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Achievement::FirstPass => "初窥门径",
+            Achievement::Combo => "百发百中",
+            Achievement::KeyMastered => "一键通关",
+            Achievement::Streak => "持之以恒",
+            Achievement::Lightning => "闪电手",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Achievement::FirstPass => "第一次完成一个方案的全部练习",
+            Achievement::Combo => "不用提示连续答对100次",
+            Achievement::KeyMastered => "练完某个键位上的所有字根",
+            Achievement::Streak => "连续30天完成每日目标",
+            Achievement::Lightning => "连续20次答对且平均反应时间低于1秒",
+        }
+    }
+
+    /// 根据当前练习状态判断是否满足条件
+    fn reached(self, game_state: &GameState, profile: &Profile) -> bool {
+        match self {
+            // 所有字根都被标记为已掌握时不算完成练习
+            Achievement::FirstPass => game_state.total_practice > 0 && game_state.is_game_over(),
+            Achievement::Combo => game_state.combo >= COMBO_TARGET,
+            Achievement::KeyMastered => key_mastered(game_state),
+            Achievement::Streak => profile.streak() >= STREAK_TARGET,
+            Achievement::Lightning => {
+                let results = &game_state.recent_results;
+                let latencies: Vec<u64> = results
+                    .iter()
+                    .rev()
+                    .take(LIGHTNING_SAMPLES)
+                    .take_while(|r| r.correct)
+                    .filter_map(|r| r.latency_ms)
+                    .collect();
+                latencies.len() == LIGHTNING_SAMPLES
+                    && latencies.iter().sum::<u64>() / (LIGHTNING_SAMPLES as u64) < LIGHTNING_MS
+            }
+        }
+    }
+}
+
+/// 刚作答的字根所在键位上的字根是否都已练完(被排除的字根不算)
+fn key_mastered(game_state: &GameState) -> bool {
+    let Some(key) = game_state.last_big_code.as_deref() else {
+        return false;
+    };
+    let mut on_key = game_state
+        .radicals
+        .iter()
+        .filter(|r| r.big_code.eq_ignore_ascii_case(key))
+        .peekable();
+    on_key.peek().is_some()
+        && on_key.all(|r| game_state.remaining_practice.get(&r.text).is_none_or(|&c| c == 0))
+}
+
+/// 检查并记录新达成的成就，有新成就时保存档案
+pub fn check(game_state: &GameState, profile: &mut Profile) -> Result<Vec<Achievement>> {
+    let unlocked: Vec<Achievement> = Achievement::ALL
+        .into_iter()
+        .filter(|a| !profile.achievements.contains_key(a.id()))
+        .filter(|a| a.reached(game_state, profile))
+        .collect();
+    for achievement in &unlocked {
+        profile.unlock(achievement.id());
+    }
+    if !unlocked.is_empty() {
+        profile.save()?;
+    }
+    Ok(unlocked)
+}
+
+/// 成就提示文字，如"🏆 达成成就: 闪电手"
+pub fn toast_text(unlocked: &[Achievement]) -> Option<String> {
+    if unlocked.is_empty() {
+        return None;
+    }
+    let names: Vec<&str> = unlocked.iter().map(|a| a.name()).collect();
+    Some(format!("🏆 达成成就: {}", names.join("、")))
+}
+
+/// 成就列表界面，已达成的显示日期
+pub fn show_list(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    profile: &Profile,
+) -> Result<()> {
+    loop {
+        terminal.draw(|f| {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .margin(1)
+                .constraints([
+                    Constraint::Length(3),
+                    Constraint::Min(5),
+                    Constraint::Length(3),
+                ])
+                .split(f.area());
+
+            let done = Achievement::ALL
+                .iter()
+                .filter(|a| profile.achievements.contains_key(a.id()))
+                .count();
+            let title = Paragraph::new(format!(
+                "成就 {}/{} (档案: {})",
+                done,
+                Achievement::ALL.len(),
+                profile.name
+            ))
+            .block(Block::default().borders(Borders::ALL))
+            .alignment(Alignment::Center);
+            f.render_widget(title, chunks[0]);
+
+            let items: Vec<ListItem> = Achievement::ALL
+                .iter()
+                .map(|a| match profile.achievements.get(a.id()) {
+                    Some(date) => ListItem::new(format!(
+                        "🏆 {}  {}  ({}达成)",
+                        a.name(),
+                        a.description(),
+                        date
+                    ))
+                    .style(Style::default().fg(Color::Yellow)),
+                    None => ListItem::new(format!("   {}  {}", a.name(), a.description()))
+                        .style(Style::default().fg(Color::DarkGray)),
+                })
+                .collect();
+            let list = List::new(items).block(Block::default().borders(Borders::ALL));
+            f.render_widget(list, chunks[1]);
+
+            let help = Paragraph::new("ESC/Enter: 返回")
+                .block(Block::default().borders(Borders::ALL))
+                .alignment(Alignment::Center);
+            f.render_widget(help, chunks[2]);
+        })?;
+
+        if let Event::Key(key) = event::read()? {
+            #[cfg(windows)]
+            if key.kind != event::KeyEventKind::Press {
+                continue;
+            }
+            if matches!(key.code, KeyCode::Esc | KeyCode::Enter) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{AnswerRecord, GameConfig, GameMode, PracticeMode, PracticeOrder, Radical};
+    use crate::profile::DayRecord;
+    use chrono::{Duration, Local};
+    use std::collections::VecDeque;
+
+    fn radical(text: &str, code: &str) -> Radical {
+        Radical {
+            code: code.to_string(),
+            text: text.to_string(),
+            frequency: 0,
+            big_code: code[..1].to_string(),
+            small_code: code[1..].to_string(),
+            examples: String::new(),
+            note: String::new(),
+        }
+    }
+
+    fn state() -> GameState {
+        let config = GameConfig {
+            radical_file: String::new(),
+            frequency_file: String::new(),
+            penalty: 4,
+            min_practice_count: 1,
+            practice_mode: PracticeMode::DualCode,
+            order: PracticeOrder::Alphabetical,
+            mode: GameMode::Normal,
+            weight_power: 1.0,
+            adaptive_target: 0,
+            instant_feedback: false,
+            time_limit: 0,
+            cancelled: false,
+        };
+        let radicals = vec![
+            radical("口", "ko"),
+            radical("囗", "kw"),
+            radical("木", "mu"),
+        ];
+        GameState::new(radicals, &config)
+    }
+
+    /// 最近作答记录，从旧到新
+    fn results(records: &[(bool, u64)]) -> VecDeque<AnswerRecord> {
+        records
+            .iter()
+            .map(|&(correct, ms)| AnswerRecord {
+                correct,
+                latency_ms: Some(ms),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn first_pass_needs_an_answer() {
+        let profile = Profile::default();
+        let mut game_state = state();
+        assert!(!Achievement::FirstPass.reached(&game_state, &profile));
+
+        // 全部字根被移出练习时不算
+        game_state
+            .remaining_practice
+            .values_mut()
+            .for_each(|c| *c = 0);
+        assert!(!Achievement::FirstPass.reached(&game_state, &profile));
+        game_state.total_practice = 3;
+        assert!(Achievement::FirstPass.reached(&game_state, &profile));
+    }
+
+    #[test]
+    fn combo_threshold() {
+        let profile = Profile::default();
+        let mut game_state = state();
+        game_state.combo = COMBO_TARGET - 1;
+        assert!(!Achievement::Combo.reached(&game_state, &profile));
+        game_state.combo = COMBO_TARGET;
+        assert!(Achievement::Combo.reached(&game_state, &profile));
+    }
+
+    #[test]
+    fn key_mastered_checks_the_last_key() {
+        let profile = Profile::default();
+        let mut game_state = state();
+        assert!(!Achievement::KeyMastered.reached(&game_state, &profile));
+
+        game_state.last_big_code = Some("k".to_string());
+        game_state.remaining_practice.insert("口".to_string(), 0);
+        assert!(!Achievement::KeyMastered.reached(&game_state, &profile));
+        // 被移出练习的字根不影响
+        game_state.remaining_practice.remove("囗");
+        assert!(Achievement::KeyMastered.reached(&game_state, &profile));
+
+        // 键位上没有字根时不算
+        game_state.last_big_code = Some("x".to_string());
+        assert!(!Achievement::KeyMastered.reached(&game_state, &profile));
+    }
+
+    #[test]
+    fn streak_threshold() {
+        let game_state = state();
+        let mut profile = Profile::default();
+        let today = Local::now().date_naive();
+        let met = DayRecord {
+            answers: 200,
+            correct: 200,
+            seconds: 0,
+        };
+        // 从昨天开始连续STREAK_TARGET - 1天
+        for days in 1..STREAK_TARGET as i64 {
+            let date = (today - Duration::days(days))
+                .format("%Y-%m-%d")
+                .to_string();
+            profile.days.insert(date, met.clone());
+        }
+        assert!(!Achievement::Streak.reached(&game_state, &profile));
+        profile
+            .days
+            .insert(today.format("%Y-%m-%d").to_string(), met);
+        assert!(Achievement::Streak.reached(&game_state, &profile));
+    }
+
+    #[test]
+    fn lightning_uses_the_latest_correct_run() {
+        let profile = Profile::default();
+        let mut game_state = state();
+        let fast = [(true, 900); LIGHTNING_SAMPLES];
+
+        // 作答次数不足
+        game_state.recent_results = results(&fast[1..]);
+        assert!(!Achievement::Lightning.reached(&game_state, &profile));
+
+        game_state.recent_results = results(&fast);
+        assert!(Achievement::Lightning.reached(&game_state, &profile));
+
+        // 只看最近LIGHTNING_SAMPLES次，更早的答错和慢速作答不影响
+        let mut records = vec![(false, 5000), (true, 9000)];
+        records.extend(fast);
+        game_state.recent_results = results(&records);
+        assert!(Achievement::Lightning.reached(&game_state, &profile));
+
+        // 窗口内有一次答错就中断
+        records[5] = (false, 500);
+        game_state.recent_results = results(&records);
+        assert!(!Achievement::Lightning.reached(&game_state, &profile));
+
+        // 平均反应时间正好是上限时不算
+        game_state.recent_results = results(&[(true, LIGHTNING_MS); LIGHTNING_SAMPLES]);
+        assert!(!Achievement::Lightning.reached(&game_state, &profile));
+    }
+}
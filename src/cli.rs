@@ -0,0 +1,444 @@
+use crate::convert::{
+    convert_radicals, generate_corpus_counts, ConversionRules, DEFAULT_CHAR_FREQ_PATH,
+    DEFAULT_RULES_PATH,
+};
+use crate::resources;
+use crate::versus::{Role, DEFAULT_PORT};
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::net::{Ipv6Addr, SocketAddr};
+
+/// 命令行子命令
+pub enum Command {
+    Play {
+        profile: Option<String>, // 用户档案名称(不指定时使用当前用户名)
+    },
+    Versus {
+        profile: Option<String>, // 用户档案名称
+        role: Role,              // 主机或加入方
+    },
+    Convert {
+        input: String,  // 拆分表文件
+        codes: String,  // 编码输出文件
+        counts: String, // 频率输出文件
+        report: Option<String>, // 诊断报告输出文件(不指定时打印到标准输出)
+        rules: Option<String>,  // 转换规则文件
+        corpus: Option<String>, // 挑选例字用的语料或字频表
+    },
+    Counts {
+        input: String,         // 拆分表文件
+        corpus: String,        // 语料或字频表文件
+        output: String,        // 频率输出文件
+        rules: Option<String>, // 转换规则文件
+    },
+    Help,
+}
+
+const USAGE: &str = "\
+用法:
+  yu-practice-game [--profile <名称>]    进入练习界面，档案默认为当前用户名
+  yu-practice-game convert <拆分表> [选项]  从拆分表导出字根编码和频率文件
+  yu-practice-game counts <拆分表> --corpus <语料> [选项]
+                                         按语料中的实际用字统计字根频率
+  yu-practice-game host [选项]           作为主机等待对手连接，进行联网对战
+  yu-practice-game join <地址[:端口]>    连接到主机进行联网对战
+
+convert 选项:
+  --codes <文件>    编码输出文件 (默认: {data}/yucode-custom.txt)
+  --counts <文件>   频率输出文件 (默认: {data}/counts-custom.txt)
+  --report <文件>   诊断报告输出文件 (默认打印到标准输出)
+  --rules <文件>    字根别名和编码字符映射规则 (默认: {rules})
+  --corpus <文件>   按语料字频挑选每个字根的例字 (默认: {freq})
+
+counts 选项:
+  --corpus <文件>   纯文本语料，或每行“字 次数”的字频表
+  --output <文件>   频率输出文件 (默认: {data}/counts-corpus.txt)
+  --rules <文件>    同 convert
+
+host 选项:
+  --port <端口>       监听端口 (默认: {port})
+  --seconds <秒数>    对战时限 (默认: 120)
+  --questions <题数>  题目数量 (默认: 30)
+host 和 join 都可以用 --profile <名称> 指定档案
+
+{data} 为用户数据目录，其中的方案文件会出现在设置的方案列表中
+";
+
+impl Command {
+    /// 解析命令行参数(不含程序名)
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let Some(sub) = args.first() else {
+            return Ok(Command::Play { profile: None });
+        };
+        match sub.as_str() {
+            "-h" | "--help" | "help" => Ok(Command::Help),
+            "--profile" => {
+                let mut iter = args[1..].iter();
+                let profile = option_value(&mut iter, sub)?;
+                match iter.next() {
+                    Some(extra) => Err(anyhow!("多余的参数: {}", extra)),
+                    None => Ok(Command::Play {
+                        profile: Some(profile),
+                    }),
+                }
+            }
+            "convert" => {
+                let mut input = None;
+                let mut codes = resources::user_data_path("yucode-custom.txt");
+                let mut counts = resources::user_data_path("counts-custom.txt");
+                let mut report = None;
+                let mut rules = None;
+                let mut corpus = None;
+                let mut iter = args[1..].iter();
+                while let Some(arg) = iter.next() {
+                    match arg.as_str() {
+                        "--codes" => codes = option_value(&mut iter, arg)?,
+                        "--corpus" => corpus = Some(option_value(&mut iter, arg)?),
+                        "--counts" => counts = option_value(&mut iter, arg)?,
+                        "--report" => report = Some(option_value(&mut iter, arg)?),
+                        "--rules" => rules = Some(option_value(&mut iter, arg)?),
+                        other if other.starts_with('-') => {
+                            return Err(anyhow!("未知选项: {}", other))
+                        }
+                        other if input.is_none() => input = Some(other.to_string()),
+                        other => return Err(anyhow!("多余的参数: {}", other)),
+                    }
+                }
+                let input = input.ok_or_else(|| anyhow!("缺少拆分表文件参数"))?;
+                Ok(Command::Convert {
+                    input,
+                    codes,
+                    counts,
+                    report,
+                    rules,
+                    corpus,
+                })
+            }
+            "counts" => {
+                let mut input = None;
+                let mut corpus = None;
+                let mut output = resources::user_data_path("counts-corpus.txt");
+                let mut rules = None;
+                let mut iter = args[1..].iter();
+                while let Some(arg) = iter.next() {
+                    match arg.as_str() {
+                        "--corpus" => corpus = Some(option_value(&mut iter, arg)?),
+                        "--output" => output = option_value(&mut iter, arg)?,
+                        "--rules" => rules = Some(option_value(&mut iter, arg)?),
+                        other if other.starts_with('-') => {
+                            return Err(anyhow!("未知选项: {}", other))
+                        }
+                        other if input.is_none() => input = Some(other.to_string()),
+                        other => return Err(anyhow!("多余的参数: {}", other)),
+                    }
+                }
+                Ok(Command::Counts {
+                    input: input.ok_or_else(|| anyhow!("缺少拆分表文件参数"))?,
+                    corpus: corpus.ok_or_else(|| anyhow!("缺少 --corpus 语料文件"))?,
+                    output,
+                    rules,
+                })
+            }
+            "host" => {
+                let mut profile = None;
+                let mut port = DEFAULT_PORT;
+                let mut seconds = 120;
+                let mut questions = 30;
+                let mut iter = args[1..].iter();
+                while let Some(arg) = iter.next() {
+                    match arg.as_str() {
+                        "--profile" => profile = Some(option_value(&mut iter, arg)?),
+                        "--port" => port = option_number(&mut iter, arg)?,
+                        "--seconds" => seconds = option_number(&mut iter, arg)?,
+                        "--questions" => questions = option_number(&mut iter, arg)?,
+                        other if other.starts_with('-') => {
+                            return Err(anyhow!("未知选项: {}", other))
+                        }
+                        other => return Err(anyhow!("多余的参数: {}", other)),
+                    }
+                }
+                if seconds == 0 || questions == 0 {
+                    return Err(anyhow!("对战时限和题目数量必须大于0"));
+                }
+                Ok(Command::Versus {
+                    profile,
+                    role: Role::Host {
+                        port,
+                        seconds,
+                        questions,
+                    },
+                })
+            }
+            "join" => {
+                let mut profile = None;
+                let mut address = None;
+                let mut iter = args[1..].iter();
+                while let Some(arg) = iter.next() {
+                    match arg.as_str() {
+                        "--profile" => profile = Some(option_value(&mut iter, arg)?),
+                        other if other.starts_with('-') => {
+                            return Err(anyhow!("未知选项: {}", other))
+                        }
+                        other if address.is_none() => address = Some(other.to_string()),
+                        other => return Err(anyhow!("多余的参数: {}", other)),
+                    }
+                }
+                let address = address.ok_or_else(|| anyhow!("缺少主机地址参数"))?;
+                Ok(Command::Versus {
+                    profile,
+                    role: Role::Join {
+                        address: with_default_port(&address),
+                    },
+                })
+            }
+            other => Err(anyhow!("未知子命令: {}", other)),
+        }
+    }
+
+    /// 执行非交互式子命令
+    pub fn run(self) -> Result<()> {
+        match self {
+            Command::Play { .. } | Command::Versus { .. } => Ok(()),
+            Command::Help => {
+                print!("{}", usage());
+                Ok(())
+            }
+            Command::Convert {
+                input,
+                codes,
+                counts,
+                report,
+                rules,
+                corpus,
+            } => {
+                let rules = ConversionRules::load(rules.as_deref())?;
+                let summary =
+                    convert_radicals(&input, &codes, &counts, &rules, corpus.as_deref())?;
+                println!("方案: {}", summary.meta.display_name());
+                println!("已处理拆分表 {} 行", summary.lines);
+                println!("编码文件: {} ({} 个字根)", codes, summary.codes);
+                println!("频率文件: {} ({} 个字根)", counts, summary.counted);
+                if summary.report.is_clean() {
+                    println!("诊断: 没有发现问题");
+                } else {
+                    println!("诊断: {}", summary.report.brief());
+                }
+                match report {
+                    Some(path) => {
+                        fs::write(&path, summary.report.to_string())?;
+                        println!("诊断报告: {}", path);
+                    }
+                    None if !summary.report.is_clean() => {
+                        println!();
+                        print!("{}", summary.report);
+                    }
+                    None => {}
+                }
+                Ok(())
+            }
+            Command::Counts {
+                input,
+                corpus,
+                output,
+                rules,
+            } => {
+                let rules = ConversionRules::load(rules.as_deref())?;
+                let summary = generate_corpus_counts(&input, &corpus, &output, &rules)?;
+                let coverage = if summary.total > 0 {
+                    summary.covered as f64 / summary.total as f64 * 100.0
+                } else {
+                    0.0
+                };
+                println!("方案: {}", summary.meta.display_name());
+                println!(
+                    "语料汉字: {} 个，其中 {} 个有拆分 ({:.2}%)",
+                    summary.total, summary.covered, coverage
+                );
+                println!("频率文件: {} ({} 个字根)", output, summary.radicals);
+                if !summary.missing.is_empty() {
+                    println!("没有拆分的常见字: {}", summary.missing.join(" "));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// 主机地址省略端口时补上默认端口，IPv6地址加上方括号，如`::1`补全为`[::1]:7878`
+fn with_default_port(address: &str) -> String {
+    if address.parse::<SocketAddr>().is_ok() {
+        return address.to_string();
+    }
+    if let Ok(ip) = address.parse::<Ipv6Addr>() {
+        return format!("[{}]:{}", ip, DEFAULT_PORT);
+    }
+    // 带方括号的IPv6地址只有以"]"结尾时才没有端口；其余含":"的是"主机名:端口"
+    let has_port = if address.starts_with('[') {
+        !address.ends_with(']')
+    } else {
+        address.contains(':')
+    };
+    if has_port {
+        address.to_string()
+    } else {
+        format!("{}:{}", address, DEFAULT_PORT)
+    }
+}
+
+/// 打印用法说明到标准错误
+pub fn print_usage() {
+    eprint!("{}", usage());
+}
+
+fn usage() -> String {
+    USAGE
+        .replace("{rules}", DEFAULT_RULES_PATH)
+        .replace("{freq}", DEFAULT_CHAR_FREQ_PATH)
+        .replace("{port}", &DEFAULT_PORT.to_string())
+        .replace(
+            "{data}",
+            &resources::user_data_dir().map_or(".".to_string(), |d| d.display().to_string()),
+        )
+}
+
+fn option_value<'a>(iter: &mut impl Iterator<Item = &'a String>, name: &str) -> Result<String> {
+    iter.next()
+        .cloned()
+        .ok_or_else(|| anyhow!("选项 {} 缺少参数", name))
+}
+
+fn option_number<'a, T: std::str::FromStr>(
+    iter: &mut impl Iterator<Item = &'a String>,
+    name: &str,
+) -> Result<T> {
+    let value = option_value(iter, name)?;
+    value
+        .parse()
+        .map_err(|_| anyhow!("选项 {} 需要数字参数，而不是 {}", name, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command> {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        Command::parse(&args)
+    }
+
+    #[test]
+    fn no_arguments_starts_game() {
+        assert!(matches!(parse(&[]), Ok(Command::Play { profile: None })));
+    }
+
+    #[test]
+    fn profile_option() {
+        let command = parse(&["--profile", "alice"]).unwrap();
+        assert!(matches!(command, Command::Play { profile: Some(p) } if p == "alice"));
+        assert!(parse(&["--profile"]).is_err());
+        assert!(parse(&["--profile", "alice", "extra"]).is_err());
+    }
+
+    #[test]
+    fn help() {
+        for arg in ["-h", "--help", "help"] {
+            assert!(matches!(parse(&[arg]), Ok(Command::Help)));
+        }
+    }
+
+    #[test]
+    fn convert_defaults() {
+        let Command::Convert {
+            input,
+            codes,
+            counts,
+            report,
+            rules,
+            corpus,
+        } = parse(&["convert", "chaifen.csv"]).unwrap()
+        else {
+            panic!("应解析为convert子命令");
+        };
+        assert_eq!(input, "chaifen.csv");
+        assert_eq!(codes, resources::user_data_path("yucode-custom.txt"));
+        assert_eq!(counts, resources::user_data_path("counts-custom.txt"));
+        assert!(report.is_none() && rules.is_none() && corpus.is_none());
+    }
+
+    #[test]
+    fn convert_options() {
+        let Command::Convert {
+            input,
+            codes,
+            counts,
+            report,
+            rules,
+            corpus,
+        } = parse(&[
+            "convert", "--codes", "a.txt", "in.csv", "--counts", "b.txt", "--report", "r.txt",
+            "--rules", "x.conf", "--corpus", "c.txt",
+        ])
+        .unwrap()
+        else {
+            panic!("应解析为convert子命令");
+        };
+        assert_eq!(input, "in.csv");
+        assert_eq!(codes, "a.txt");
+        assert_eq!(counts, "b.txt");
+        assert_eq!(report.as_deref(), Some("r.txt"));
+        assert_eq!(rules.as_deref(), Some("x.conf"));
+        assert_eq!(corpus.as_deref(), Some("c.txt"));
+    }
+
+    #[test]
+    fn convert_errors() {
+        assert!(parse(&["convert"]).is_err());
+        assert!(parse(&["convert", "a.csv", "b.csv"]).is_err());
+        assert!(parse(&["convert", "a.csv", "--unknown"]).is_err());
+        assert!(parse(&["convert", "a.csv", "--codes"]).is_err());
+        assert!(parse(&["unknown"]).is_err());
+    }
+
+    #[test]
+    fn counts_requires_corpus() {
+        assert!(parse(&["counts", "in.csv"]).is_err());
+        let Command::Counts {
+            input,
+            corpus,
+            output,
+            rules,
+        } = parse(&["counts", "in.csv", "--corpus", "c.txt"]).unwrap()
+        else {
+            panic!("应解析为counts子命令");
+        };
+        assert_eq!(input, "in.csv");
+        assert_eq!(corpus, "c.txt");
+        assert_eq!(output, resources::user_data_path("counts-corpus.txt"));
+        assert!(rules.is_none());
+    }
+
+    #[test]
+    fn join_address_gets_default_port() {
+        let cases = [
+            ("127.0.0.1", "127.0.0.1:7878"),
+            ("127.0.0.1:9000", "127.0.0.1:9000"),
+            ("::1", "[::1]:7878"),
+            ("fe80::1", "[fe80::1]:7878"),
+            ("[::1]", "[::1]:7878"),
+            ("[::1]:9000", "[::1]:9000"),
+            ("lunch-room", "lunch-room:7878"),
+            ("lunch-room:9000", "lunch-room:9000"),
+        ];
+        for (input, expected) in cases {
+            let Command::Versus {
+                role: Role::Join { address },
+                ..
+            } = parse(&["join", input]).unwrap()
+            else {
+                panic!("应解析为join子命令");
+            };
+            assert_eq!(address, expected, "{}", input);
+        }
+        assert!(parse(&["join"]).is_err());
+    }
+}
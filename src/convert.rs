@@ -0,0 +1,941 @@
+use crate::resources;
+use crate::scheme::SchemeMeta;
+use anyhow::{anyhow, Context, Result};
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// 转换结果摘要
+#[derive(Debug, Default)]
+pub struct ConversionSummary {
+    pub lines: usize,             // 处理的拆分行数
+    pub codes: usize,             // 导出的字根编码数
+    pub counted: usize,           // 统计了频率的字根数
+    pub meta: SchemeMeta,         // 从文件头读取的方案元数据
+    pub report: ConversionReport, // 诊断报告
+}
+
+/// 转换诊断报告，列出被跳过或有问题的数据，避免错误的转换结果看起来一切正常
+#[derive(Debug, Default)]
+pub struct ConversionReport {
+    pub skipped: Vec<SkippedLine>,             // 被跳过的拆分行
+    pub uncoded: Vec<String>,                  // 出现在拆分中但没有得到编码的字根
+    pub conflicts: Vec<(String, Vec<String>)>, // 出现互相矛盾编码的字根
+}
+
+/// 被跳过的拆分行
+#[derive(Debug)]
+pub struct SkippedLine {
+    pub line_no: usize,  // 行号(从1开始)
+    pub reason: String,  // 跳过原因
+    pub content: String, // 原始内容
+}
+
+impl ConversionReport {
+    /// 是否没有任何问题
+    pub fn is_clean(&self) -> bool {
+        self.skipped.is_empty() && self.uncoded.is_empty() && self.conflicts.is_empty()
+    }
+
+    /// 问题数量的一行摘要
+    pub fn brief(&self) -> String {
+        format!(
+            "跳过{}行，{}个字根没有编码，{}个字根编码冲突",
+            self.skipped.len(),
+            self.uncoded.len(),
+            self.conflicts.len()
+        )
+    }
+}
+
+impl fmt::Display for ConversionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# 转换诊断报告")?;
+        writeln!(f, "# {}", self.brief())?;
+        writeln!(f)?;
+        writeln!(f, "## 跳过的行 ({})", self.skipped.len())?;
+        for skipped in &self.skipped {
+            writeln!(
+                f,
+                "第{}行: {}: {}",
+                skipped.line_no, skipped.reason, skipped.content
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(f, "## 没有编码的字根 ({})", self.uncoded.len())?;
+        for radical in &self.uncoded {
+            writeln!(f, "{}", radical)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "## 编码冲突的字根 ({})", self.conflicts.len())?;
+        for (radical, codes) in &self.conflicts {
+            writeln!(f, "{}: {}", radical, codes.join(" / "))?;
+        }
+        Ok(())
+    }
+}
+
+/// 每个字根导出的例字数量
+const EXAMPLE_COUNT: usize = 3;
+
+/// 默认转换规则文件路径
+pub const DEFAULT_RULES_PATH: &str = "res/convert-rules.conf";
+
+/// 内置的默认转换规则，找不到规则文件时使用
+const BUILTIN_RULES: &str = include_str!("../res/convert-rules.conf");
+
+/// 默认字频表路径，没有指定语料时按它挑选例字
+pub const DEFAULT_CHAR_FREQ_PATH: &str = "res/char-freq.tsv";
+
+/// 内置的常用字频表，找不到字频表文件时使用
+const BUILTIN_CHAR_FREQ: &str = include_str!("../res/char-freq.tsv");
+
+/// 转换规则：字根别名和编码字符映射
+#[derive(Debug, Default)]
+pub struct ConversionRules {
+    aliases: HashMap<String, Vec<String>>, // 字根 -> 使用相同编码的别名字根
+    char_map: HashMap<char, char>,         // 编码中的特殊字符 -> 小码字母
+}
+
+impl ConversionRules {
+    /// 解析规则文本
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut rules = Self::default();
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            match parts.as_slice() {
+                ["alias", radical, alias] => {
+                    rules
+                        .aliases
+                        .entry(radical.to_string())
+                        .or_default()
+                        .push(alias.to_string());
+                }
+                ["map", from, to] => {
+                    let to = single_char(to)
+                        .filter(char::is_ascii_lowercase)
+                        .ok_or_else(|| {
+                            anyhow!("规则第{}行: 映射目标必须是单个小写字母", idx + 1)
+                        })?;
+                    let range: Vec<char> = from.chars().collect();
+                    match range.as_slice() {
+                        [c] => {
+                            rules.char_map.insert(*c, to);
+                        }
+                        [start, '-', end] if start <= end => {
+                            for (offset, c) in (*start..=*end).enumerate() {
+                                let mapped = char::from_u32(to as u32 + offset as u32)
+                                    .filter(char::is_ascii_lowercase)
+                                    .ok_or_else(|| {
+                                        anyhow!("规则第{}行: 映射超出a-z范围", idx + 1)
+                                    })?;
+                                rules.char_map.insert(c, mapped);
+                            }
+                        }
+                        _ => return Err(anyhow!("规则第{}行: 无效的字符区间 {}", idx + 1, from)),
+                    }
+                }
+                _ => return Err(anyhow!("规则第{}行: 无法识别的规则 {}", idx + 1, line)),
+            }
+        }
+        Ok(rules)
+    }
+
+    /// 加载规则：指定了文件时读取该文件，否则依次尝试默认规则文件和内置规则
+    pub fn load(path: Option<&str>) -> Result<Self> {
+        match path {
+            Some(path) => Self::parse(&fs::read_to_string(path)?),
+            None => match fs::read_to_string(DEFAULT_RULES_PATH) {
+                Ok(text) => Self::parse(&text),
+                Err(_) => Self::parse(BUILTIN_RULES),
+            },
+        }
+    }
+
+    /// 与指定字根共用编码的别名
+    fn aliases_of(&self, radical: &str) -> &[String] {
+        self.aliases.get(radical).map_or(&[], |v| v.as_slice())
+    }
+}
+
+fn single_char(s: &str) -> Option<char> {
+    let mut chars = s.chars();
+    let c = chars.next()?;
+    chars.next().is_none().then_some(c)
+}
+
+/// 拆分表中的一行有效数据
+struct ChaifenEntry {
+    character: String,     // 汉字
+    radicals: Vec<String>, // 拆分出的字根
+    codes: Vec<String>,    // 与字根一一对应的编码
+    cjk: bool,             // 是否属于CJK基本区(计入频率统计)
+}
+
+/// 解析后的拆分表
+struct Chaifen {
+    meta: SchemeMeta,
+    entries: Vec<ChaifenEntry>,
+    lines: usize,
+    skipped: Vec<SkippedLine>,
+}
+
+/// 读取并解析宇浩单字拆分表
+fn read_chaifen(input_path: &str, rules: &ConversionRules) -> Result<Chaifen> {
+    // 检查输入文件是否存在
+    if !Path::new(input_path).exists() {
+        return Err(anyhow::anyhow!("拆分表文件不存在: {}", input_path));
+    }
+
+    // 读取输入文件
+    let file = File::open(input_path)?;
+    let reader = BufReader::new(file);
+
+    let mut processing = false;
+    let mut is_sun_moon = false;
+    let mut header: Vec<String> = Vec::new();
+    let mut chaifen = Chaifen {
+        meta: SchemeMeta::default(),
+        entries: Vec::new(),
+        lines: 0,
+        skipped: Vec::new(),
+    };
+    let file_name = Path::new(input_path)
+        .file_name()
+        .map_or(String::new(), |n| n.to_string_lossy().to_string());
+
+    for (line_idx, line) in reader.lines().enumerate() {
+        let line = line?;
+
+        // "..."之前是YAML文件头，读完后解析方案元数据
+        if !processing {
+            if line.trim().starts_with("...") {
+                processing = true;
+                chaifen.meta = SchemeMeta::from_yaml_header(&header, &file_name);
+                is_sun_moon = chaifen.meta.is_sun_moon();
+            } else {
+                header.push(line);
+            }
+            continue;
+        }
+
+        let line = line.trim();
+        let mut skip = |reason: String| {
+            chaifen.skipped.push(SkippedLine {
+                line_no: line_idx + 1,
+                reason,
+                content: line.to_string(),
+            });
+        };
+
+        // 跳过注释行和空行
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        chaifen.lines += 1;
+
+        // 解析行格式：汉字\t[拆分,编码,拼音,字符集,unicode]
+        let tab_start = line.find('\t').unwrap_or(2);
+        let character = line.get(..tab_start).unwrap_or_default().trim().to_string();
+        let rest = line.get(tab_start..).unwrap_or(line);
+        let (Some(bracket_start), Some(bracket_end)) = (rest.find('['), rest.find(']')) else {
+            skip("缺少[...]拆分数据".to_string());
+            continue;
+        };
+        if bracket_end < bracket_start {
+            skip("方括号不匹配".to_string());
+            continue;
+        }
+        let content = &rest[bracket_start + 1..bracket_end];
+        let parts: Vec<&str> = content.split(',').collect();
+        if parts.len() < 2 {
+            skip("缺少编码字段".to_string());
+            continue;
+        }
+        let radicals = parts[0].trim(); // 拆分部分
+        let codes = parts[1].trim(); // 编码部分
+
+        if radicals.is_empty() {
+            skip("拆分为空".to_string());
+            continue;
+        }
+
+        // 处理拆分和编码
+        let radical_list = extract_radicals(radicals);
+        let code_list = extract_codes(codes, is_sun_moon, rules);
+
+        if radical_list.len() != code_list.len() {
+            skip(format!(
+                "拆分与编码数量不一致({}个字根, {}个编码)",
+                radical_list.len(),
+                code_list.len()
+            ));
+            continue;
+        }
+
+        chaifen.entries.push(ChaifenEntry {
+            character,
+            radicals: radical_list,
+            codes: code_list,
+            cjk: content.contains("CJK"),
+        });
+    }
+
+    Ok(chaifen)
+}
+
+/// 检查文件是否为可以转换的宇浩单字拆分表
+pub fn check_chaifen(path: &str) -> Result<()> {
+    let chaifen = read_chaifen(path, &ConversionRules::default())?;
+    if chaifen.entries.is_empty() {
+        return Err(anyhow!("不是宇浩单字拆分表(缺少\"...\"或[拆分,编码]数据)"));
+    }
+    Ok(())
+}
+
+/// 从宇浩单字拆分表导出字根编码文件和字根频率文件
+///
+/// 编码文件每行为"编码 字根 例字"，按语料字频挑选例字，不指定语料时使用默认字频表
+pub fn convert_radicals(
+    input_path: &str,
+    code_output_path: &str,
+    counts_output_path: &str,
+    rules: &ConversionRules,
+    corpus_path: Option<&str>,
+) -> Result<ConversionSummary> {
+    let chaifen = read_chaifen(input_path, rules)?;
+    let char_freq = match corpus_path {
+        Some(path) => read_corpus(path)?,
+        None => default_char_freq(),
+    };
+    let examples = pick_examples(&chaifen.entries, &char_freq);
+
+    let mut radical_counts: HashMap<String, u64> = HashMap::new();
+    let mut radical_codes: HashMap<String, String> = HashMap::new();
+    // 每个字根作为末尾部件时出现过的所有编码，用于检查冲突
+    let mut code_variants: HashMap<String, BTreeSet<String>> = HashMap::new();
+    let mut all_radicals: BTreeSet<String> = BTreeSet::new();
+    let mut summary = ConversionSummary {
+        lines: chaifen.lines,
+        meta: chaifen.meta,
+        ..Default::default()
+    };
+    summary.report.skipped = chaifen.skipped;
+
+    for entry in &chaifen.entries {
+        let mut i = 0;
+        for (radical, code) in entry.radicals.iter().zip(entry.codes.iter()) {
+            all_radicals.insert(radical.to_string());
+            if entry.cjk {
+                // 统计字根出现次数
+                *radical_counts.entry(radical.to_string()).or_insert(0) += 1;
+            }
+
+            i += 1;
+            if i < 4 && entry.codes.len() == i {
+                code_variants
+                    .entry(radical.to_string())
+                    .or_default()
+                    .insert(code.to_string());
+                if !radical_codes.contains_key(radical)
+                    || radical_codes.get(radical).map_or("", |v| v).len() < code.len()
+                {
+                    // 记录字根编码，别名字根使用相同的编码
+                    radical_codes.insert(radical.to_string(), code.to_string());
+                    for alias in rules.aliases_of(radical) {
+                        radical_codes.insert(alias.to_string(), code.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    // 出现在拆分中却从未作为末尾部件得到编码的字根
+    summary.report.uncoded = all_radicals
+        .into_iter()
+        .filter(|r| !radical_codes.contains_key(r))
+        .collect();
+
+    // 同一字根的编码互不为前缀时视为冲突(较短的编码通常只是截断)
+    let mut conflicts: Vec<(String, Vec<String>)> = code_variants
+        .into_iter()
+        .filter(|(_, codes)| {
+            codes.iter().any(|a| {
+                codes
+                    .iter()
+                    .any(|b| !a.starts_with(b.as_str()) && !b.starts_with(a.as_str()))
+            })
+        })
+        .map(|(radical, codes)| (radical, codes.into_iter().collect()))
+        .collect();
+    conflicts.sort();
+    summary.report.conflicts = conflicts;
+
+    // 按编码排序并写入编码文件
+    let mut sorted_codes: Vec<(&String, &String)> = radical_codes.iter().collect();
+    sorted_codes.sort_by(|a, b| a.1.cmp(b.1));
+
+    let mut code_file = create_output(code_output_path)?;
+    summary.meta.write_header(&mut code_file)?;
+    for (radical, code) in sorted_codes {
+        match examples.get(radical.as_str()) {
+            Some(chars) => writeln!(code_file, "{} {} {}", code.trim(), radical.trim(), chars)?,
+            None => writeln!(code_file, "{} {}", code.trim(), radical.trim())?,
+        }
+    }
+
+    // 按频率排序并写入频率文件
+    write_counts(counts_output_path, &summary.meta, &radical_counts)?;
+
+    summary.codes = radical_codes.len();
+    summary.counted = radical_counts.len();
+    Ok(summary)
+}
+
+/// 例字候选：(字频倒序, 部件数, 拆分表中的顺序, 汉字)，按元组顺序排序
+type ExampleCandidate<'a> = (Reverse<u64>, usize, usize, &'a str);
+
+/// 为每个字根挑选包含它的例字
+///
+/// 只从CJK基本区的多部件汉字中挑选；按字频从高到低，
+/// 字频相同(如都不在字频表中)时优先部件少的字，再按拆分表中的顺序
+fn pick_examples(
+    entries: &[ChaifenEntry],
+    char_freq: &HashMap<String, u64>,
+) -> HashMap<String, String> {
+    let mut candidates: HashMap<&str, Vec<ExampleCandidate>> = HashMap::new();
+    for (order, entry) in entries.iter().enumerate() {
+        if !entry.cjk || entry.radicals.len() < 2 || entry.character.chars().count() != 1 {
+            continue;
+        }
+        let freq = char_freq.get(&entry.character).copied().unwrap_or(0);
+        let radicals: BTreeSet<&str> = entry.radicals.iter().map(String::as_str).collect();
+        for radical in radicals {
+            candidates.entry(radical).or_default().push((
+                Reverse(freq),
+                entry.radicals.len(),
+                order,
+                &entry.character,
+            ));
+        }
+    }
+    candidates
+        .into_iter()
+        .map(|(radical, mut list)| {
+            list.sort_unstable();
+            let mut chosen: Vec<&str> = Vec::new();
+            for (_, _, _, character) in list {
+                if !chosen.contains(&character) {
+                    chosen.push(character);
+                }
+                if chosen.len() == EXAMPLE_COUNT {
+                    break;
+                }
+            }
+            (radical.to_string(), chosen.concat())
+        })
+        .collect()
+}
+
+/// 为没有例字列的字根文件挑选例字：使用res目录或用户数据目录中方案标识和版本号都相同的拆分表
+pub fn find_examples(meta: &SchemeMeta) -> Option<HashMap<String, String>> {
+    examples_from_tables(meta, &resources::chaifen_files())
+}
+
+/// 在给定的拆分表中找到第一个方案相同的，按默认字频表挑选例字
+fn examples_from_tables(meta: &SchemeMeta, paths: &[PathBuf]) -> Option<HashMap<String, String>> {
+    paths.iter().find_map(|path| {
+        let chaifen = read_chaifen(&path.to_string_lossy(), &ConversionRules::default()).ok()?;
+        let same_scheme = chaifen.meta.name == meta.name && chaifen.meta.version == meta.version;
+        (same_scheme && !chaifen.entries.is_empty())
+            .then(|| pick_examples(&chaifen.entries, &default_char_freq()))
+    })
+}
+
+/// 语料频率统计结果摘要
+#[derive(Debug, Default)]
+pub struct CorpusSummary {
+    pub meta: SchemeMeta,     // 拆分表的方案元数据
+    pub total: u64,           // 语料中的汉字总数(按频率加权)
+    pub covered: u64,         // 在拆分表中找到拆分的汉字数
+    pub radicals: usize,      // 统计到的字根数
+    pub missing: Vec<String>, // 没有拆分的汉字(按出现次数从多到少，最多20个)
+}
+
+/// 按语料中的实际用字统计字根频率
+///
+/// 语料可以是纯文本，也可以是"字 次数"格式的字频表；
+/// 每个汉字按出现次数把权重加到它拆分出的所有字根上
+pub fn generate_corpus_counts(
+    chaifen_path: &str,
+    corpus_path: &str,
+    output_path: &str,
+    rules: &ConversionRules,
+) -> Result<CorpusSummary> {
+    let chaifen = read_chaifen(chaifen_path, rules)?;
+    let char_freq = read_corpus(corpus_path)?;
+
+    let decompositions: HashMap<&str, &[String]> = chaifen
+        .entries
+        .iter()
+        .map(|e| (e.character.as_str(), e.radicals.as_slice()))
+        .collect();
+
+    let mut radical_counts: HashMap<String, u64> = HashMap::new();
+    let mut missing: Vec<(String, u64)> = Vec::new();
+    let mut summary = CorpusSummary::default();
+    for (character, freq) in char_freq {
+        summary.total += freq;
+        match decompositions.get(character.as_str()) {
+            Some(radicals) => {
+                summary.covered += freq;
+                for radical in radicals.iter() {
+                    *radical_counts.entry(radical.to_string()).or_insert(0) += freq;
+                }
+            }
+            None => missing.push((character, freq)),
+        }
+    }
+    missing.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    summary.missing = missing.into_iter().take(20).map(|(c, _)| c).collect();
+
+    write_counts(output_path, &chaifen.meta, &radical_counts)?;
+    summary.radicals = radical_counts.len();
+    summary.meta = chaifen.meta;
+    Ok(summary)
+}
+
+/// 读取语料并统计每个汉字的出现次数
+fn read_corpus(path: &str) -> Result<HashMap<String, u64>> {
+    let content =
+        fs::read_to_string(path).map_err(|e| anyhow!("无法读取语料文件 {}: {}", path, e))?;
+    Ok(parse_corpus(&content))
+}
+
+/// 默认字频表：依次尝试默认字频表文件和内置字频表
+fn default_char_freq() -> HashMap<String, u64> {
+    match fs::read_to_string(DEFAULT_CHAR_FREQ_PATH) {
+        Ok(text) => parse_corpus(&text),
+        Err(_) => parse_corpus(BUILTIN_CHAR_FREQ),
+    }
+}
+
+/// 统计语料中每个汉字的出现次数
+///
+/// 除#注释行外的每个非空行都是"文本 数字"格式时按字频表处理(词频表中的词拆成单字累计)，
+/// 否则逐字统计全文中的汉字
+fn parse_corpus(content: &str) -> HashMap<String, u64> {
+    let freq_list: Option<Vec<(&str, u64)>> = content
+        .lines()
+        .filter(|l| !l.trim().is_empty() && !l.starts_with('#'))
+        .map(|l| {
+            let mut parts = l.split_whitespace();
+            let text = parts.next()?;
+            let count = parts.next()?.parse().ok()?;
+            Some((text, count))
+        })
+        .collect();
+
+    let mut char_freq: HashMap<String, u64> = HashMap::new();
+    match freq_list {
+        Some(list) if !list.is_empty() => {
+            for (text, count) in list {
+                for c in text.chars().filter(|c| is_han(*c)) {
+                    *char_freq.entry(c.to_string()).or_insert(0) += count;
+                }
+            }
+        }
+        _ => {
+            for c in content.chars().filter(|c| is_han(*c)) {
+                *char_freq.entry(c.to_string()).or_insert(0) += 1;
+            }
+        }
+    }
+    char_freq
+}
+
+/// 是否为汉字(CJK统一表意文字及其扩展区、兼容区)
+fn is_han(c: char) -> bool {
+    matches!(c as u32,
+        0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xF900..=0xFAFF
+        | 0x20000..=0x3FFFF)
+}
+
+/// 创建输出文件，所在目录不存在时先创建目录
+fn create_output(path: &str) -> Result<File> {
+    if let Some(dir) = Path::new(path).parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).with_context(|| format!("无法创建目录 {}", dir.display()))?;
+    }
+    File::create(path).with_context(|| format!("无法创建输出文件 {}", path))
+}
+
+/// 按频率从高到低写入频率文件
+fn write_counts(path: &str, meta: &SchemeMeta, counts: &HashMap<String, u64>) -> Result<()> {
+    let mut sorted_counts: Vec<(&String, &u64)> = counts.iter().collect();
+    sorted_counts.sort_by(|a, b| b.1.cmp(a.1));
+
+    let mut counts_file = create_output(path)?;
+    meta.write_header(&mut counts_file)?;
+    for (radical, count) in sorted_counts {
+        writeln!(counts_file, "{} {}", radical.trim(), count)?;
+    }
+    Ok(())
+}
+
+fn extract_radicals(radicals: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut current = String::new();
+    let mut in_brackets = false;
+
+    for c in radicals.chars() {
+        match c {
+            '{' => {
+                in_brackets = true;
+                current.clear();
+                current.push(c);
+            }
+            '}' => {
+                in_brackets = false;
+                if !current.is_empty() {
+                    current.push(c);
+                    result.push(current.clone());
+                    current.clear();
+                }
+            }
+            _ => {
+                if in_brackets {
+                    current.push(c);
+                } else {
+                    result.push(c.to_string());
+                }
+            }
+        }
+    }
+
+    result
+}
+
+fn extract_codes(codes: &str, is_sun_moon: bool, rules: &ConversionRules) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut current = String::new();
+    let mut in_code = false;
+
+    for c in codes.chars() {
+        if c.is_uppercase() {
+            // 大写字母开始新编码
+            if !current.is_empty() {
+                result.push(current.clone());
+                current.clear();
+            }
+            current.push(c.to_ascii_lowercase());
+            in_code = true;
+        } else if in_code && c.is_ascii_lowercase() {
+            // 小写字母继续当前编码
+            current.push(c);
+        } else if let Some(&mapped) = rules.char_map.get(&c).filter(|_| in_code) {
+            // 规则中定义的特殊字符(如圈字母)按对应字母处理
+            current.push(mapped);
+        } else {
+            // 其他字符结束当前编码
+            if !current.is_empty() {
+                result.push(current.clone());
+                current.clear();
+                in_code = false;
+            }
+        }
+    }
+
+    // 添加最后一个编码
+    if !current.is_empty() {
+        result.push(current);
+    }
+
+    // 如果编码长度>2，只取前两个字母
+    result
+        .iter()
+        .map(|code| {
+            if !is_sun_moon && code.chars().count() > 2 {
+                code.chars().take(2).collect()
+            } else {
+                code.clone()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// 测试用的临时目录，离开作用域时删除
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("yu-practice-{}-{}", name, std::process::id()));
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        /// 在目录中创建文件，返回路径
+        fn file(&self, name: &str, content: &str) -> String {
+            let path = self.0.join(name);
+            fs::write(&path, content).unwrap();
+            self.path(name)
+        }
+
+        fn path(&self, name: &str) -> String {
+            self.0.join(name).to_string_lossy().to_string()
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    const HEADER: &str = "---\nname: yujoy\nversion: 3.8.0\n...\n";
+
+    #[test]
+    fn parse_rules() {
+        let rules = ConversionRules::parse(
+            "# 注释\n\nalias {横日} {曾中}\nalias {横日} {另一}\nmap ① a\nmap ⓐ-ⓒ x\n",
+        )
+        .unwrap();
+        assert_eq!(rules.aliases_of("{横日}"), ["{曾中}", "{另一}"]);
+        assert!(rules.aliases_of("日").is_empty());
+        assert_eq!(rules.char_map.get(&'①'), Some(&'a'));
+        assert_eq!(rules.char_map.get(&'ⓐ'), Some(&'x'));
+        assert_eq!(rules.char_map.get(&'ⓒ'), Some(&'z'));
+        assert_eq!(rules.char_map.len(), 4);
+    }
+
+    #[test]
+    fn builtin_rules_parse() {
+        let rules = ConversionRules::parse(BUILTIN_RULES).unwrap();
+        assert_eq!(rules.char_map.get(&'ⓩ'), Some(&'z'));
+    }
+
+    #[test]
+    fn parse_rules_errors() {
+        let error = |text: &str| ConversionRules::parse(text).unwrap_err().to_string();
+        assert_eq!(
+            error("# 注释\nunknown a b"),
+            "规则第2行: 无法识别的规则 unknown a b"
+        );
+        assert_eq!(error("map ⓐ-ⓩ α"), "规则第1行: 映射目标必须是单个小写字母");
+        assert_eq!(error("map ① A"), "规则第1行: 映射目标必须是单个小写字母");
+        assert_eq!(error("map ① ab"), "规则第1行: 映射目标必须是单个小写字母");
+        assert_eq!(error("map ⓐ-ⓩ b"), "规则第1行: 映射超出a-z范围");
+        assert_eq!(error("map ⓩ-ⓐ a"), "规则第1行: 无效的字符区间 ⓩ-ⓐ");
+        assert!(ConversionRules::parse("alias 日").is_err());
+    }
+
+    #[test]
+    fn extract_codes_with_mapped_chars() {
+        let rules = ConversionRules::parse("map ⓐ-ⓩ a").unwrap();
+        assert_eq!(extract_codes("OdⓨYe", false, &rules), ["od", "ye"]);
+        assert_eq!(extract_codes("Oⓓⓨ,Ye", true, &rules), ["ody", "ye"]);
+        // 没有映射规则时特殊字符结束当前编码
+        assert_eq!(
+            extract_codes("OⓓYe", false, &ConversionRules::default()),
+            ["o", "ye"]
+        );
+        // 按字符而不是字节截取前两码
+        let mut rules = ConversionRules::default();
+        rules.char_map.insert('①', 'é');
+        assert_eq!(extract_codes("O①①", false, &rules), ["oé"]);
+    }
+
+    #[test]
+    fn report_lists_skipped_uncoded_and_conflicts() {
+        let dir = TempDir::new("report");
+        let chaifen = format!(
+            "{}{}",
+            HEADER,
+            "# 注释行不计入\n\
+             明\t[日月,OdYe,ming,CJK,U+660E]\n\
+             没有括号的行\n\
+             空\t[,Ab,kong,CJK,U+7A7A]\n\
+             缺\t[日月]\n\
+             数\t[日月木,OdYe,shu,CJK,U+6570]\n\
+             朋\t[月月,YeYx,peng,CJK,U+670B]\n"
+        );
+        let input = dir.file("chaifen.txt", &chaifen);
+        let summary = convert_radicals(
+            &input,
+            &dir.path("codes.txt"),
+            &dir.path("counts.txt"),
+            &ConversionRules::default(),
+            None,
+        )
+        .unwrap();
+
+        let skipped: Vec<(usize, &str)> = summary
+            .report
+            .skipped
+            .iter()
+            .map(|s| (s.line_no, s.reason.as_str()))
+            .collect();
+        assert_eq!(
+            skipped,
+            vec![
+                (7, "缺少[...]拆分数据"),
+                (8, "拆分为空"),
+                (9, "缺少编码字段"),
+                (10, "拆分与编码数量不一致(3个字根, 2个编码)"),
+            ]
+        );
+        assert_eq!(summary.lines, 6);
+        assert_eq!(summary.report.uncoded, vec!["日".to_string()]);
+        assert_eq!(
+            summary.report.conflicts,
+            vec![("月".to_string(), vec!["ye".to_string(), "yx".to_string()])]
+        );
+        assert!(!summary.report.is_clean());
+        assert_eq!(
+            summary.report.brief(),
+            "跳过4行，1个字根没有编码，1个字根编码冲突"
+        );
+
+        let text = summary.report.to_string();
+        assert!(text.contains("第7行: 缺少[...]拆分数据: 没有括号的行"));
+        assert!(text.contains("月: ye / yx"));
+    }
+
+    #[test]
+    fn clean_report() {
+        let dir = TempDir::new("clean");
+        let chaifen = format!("{}{}", HEADER, "日\t[日,Od,ri,CJK,U+65E5]\n");
+        let input = dir.file("chaifen.txt", &chaifen);
+        let summary = convert_radicals(
+            &input,
+            &dir.path("codes.txt"),
+            &dir.path("counts.txt"),
+            &ConversionRules::default(),
+            None,
+        )
+        .unwrap();
+        assert!(summary.report.is_clean());
+        assert_eq!(summary.codes, 1);
+    }
+
+    #[test]
+    fn builtin_char_freq_is_frequency_list() {
+        let freq = parse_corpus(BUILTIN_CHAR_FREQ);
+        assert_eq!(freq.len(), 6000);
+        assert!(freq["人"] > freq["捕"]);
+    }
+
+    #[test]
+    fn examples_prefer_frequent_characters() {
+        let chaifen = format!(
+            "{}{}",
+            HEADER,
+            "铺\t[钅甫,JaAf,pu,CJK,U+94FA]\n\
+             捕\t[扌甫,FaAf,bu,CJK,U+6355]\n\
+             薄\t[艹氵甫寸,CaVaAfCu,bo,CJK,U+8584]\n\
+             辅\t[车甫,CeAf,fu,CJK,U+8F85]\n\
+             哺\t[口甫,KoAf,bu,CJK,U+54FA]\n"
+        );
+        let dir = TempDir::new("examples");
+        let input = dir.file("chaifen.txt", &chaifen);
+        let rules = ConversionRules::default();
+        let entries = read_chaifen(&input, &rules).unwrap().entries;
+
+        // 默认字频表中常用的字排在前面，部件多的字也可以入选
+        let examples = pick_examples(&entries, &default_char_freq());
+        let mut chars: Vec<char> = examples["甫"].chars().collect();
+        assert_eq!(chars, ['铺', '捕', '薄']);
+
+        // 都不在字频表中时优先部件少的字，再按拆分表中的顺序
+        let examples = pick_examples(&entries, &HashMap::new());
+        chars = examples["甫"].chars().collect();
+        assert_eq!(chars, ['铺', '捕', '辅']);
+    }
+
+    #[test]
+    fn examples_from_matching_table_only() {
+        let dir = TempDir::new("find-examples");
+        let other = dir.file(
+            "yujoy-3.7.0_chaifen.txt",
+            "---\nname: yujoy\nversion: 3.7.0\n...\n铺\t[钅甫,JaAf,pu,CJK,U+94FA]\n",
+        );
+        let table = format!("{}{}", HEADER, "捕\t[扌甫,FaAf,bu,CJK,U+6355]\n");
+        let matching = dir.file("yujoy_chaifen.txt", &table);
+        let paths = [PathBuf::from(other), PathBuf::from(matching)];
+
+        let meta = |version: &str| SchemeMeta {
+            name: "yujoy".to_string(),
+            version: version.to_string(),
+            ..SchemeMeta::default()
+        };
+        let examples = examples_from_tables(&meta("3.8.0"), &paths).unwrap();
+        assert_eq!(examples["甫"], "捕");
+        assert_eq!(examples["扌"], "捕");
+        assert!(examples_from_tables(&meta("3.9.0"), &paths).is_none());
+        assert!(examples_from_tables(&meta("3.8.0"), &[]).is_none());
+    }
+
+    #[test]
+    fn read_corpus_plain_text_and_frequency_list() {
+        let dir = TempDir::new("corpus");
+        let text = dir.file("text.txt", "明明白白, abc 明\n");
+        let freq = read_corpus(&text).unwrap();
+        assert_eq!(freq.get("明"), Some(&3));
+        assert_eq!(freq.get("白"), Some(&2));
+        assert_eq!(freq.len(), 2);
+
+        // 词频表中的词拆成单字累计
+        let list = dir.file("list.txt", "# 注释\n明白 10\n明 5\n\n朋 1\n");
+        let freq = read_corpus(&list).unwrap();
+        assert_eq!(freq.get("明"), Some(&15));
+        assert_eq!(freq.get("白"), Some(&10));
+        assert_eq!(freq.get("朋"), Some(&1));
+
+        assert!(read_corpus(&dir.path("missing.txt")).is_err());
+    }
+
+    #[test]
+    fn corpus_counts_weight_radicals_by_frequency() {
+        let dir = TempDir::new("corpus-counts");
+        let chaifen = format!(
+            "{}{}",
+            HEADER,
+            "明\t[日月,OdYe,ming,CJK,U+660E]\n\
+             朋\t[月月,YeYe,peng,CJK,U+670B]\n"
+        );
+        let input = dir.file("chaifen.txt", &chaifen);
+        let corpus = dir.file("freq.txt", "明 10\n朋 3\n白 7\n的 9\n");
+        let output = dir.path("counts.txt");
+        let summary =
+            generate_corpus_counts(&input, &corpus, &output, &ConversionRules::default()).unwrap();
+
+        assert_eq!(summary.total, 29);
+        assert_eq!(summary.covered, 13);
+        assert_eq!(summary.radicals, 2);
+        assert_eq!(summary.missing, ["的", "白"]);
+        assert_eq!(summary.meta.family, "卿云");
+
+        let content = fs::read_to_string(&output).unwrap();
+        assert_eq!(
+            SchemeMeta::from_header_comments(&content),
+            Some(summary.meta)
+        );
+        let data: Vec<&str> = content.lines().filter(|l| !l.starts_with('#')).collect();
+        // 月在明中出现1次、朋中出现2次：10 + 3*2
+        assert_eq!(data, ["月 16", "日 10"]);
+    }
+}
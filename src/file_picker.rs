@@ -0,0 +1,352 @@
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Terminal,
+};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 选中文件后的校验：文件类型名称和解析函数，解析失败时显示错误原因
+#[derive(Clone, Copy)]
+pub struct Validation {
+    noun: &'static str,            // 文件类型名称
+    parse: fn(&str) -> Result<()>, // 按对应格式解析文件
+}
+
+impl Validation {
+    pub fn new(noun: &'static str, parse: fn(&str) -> Result<()>) -> Self {
+        Self { noun, parse }
+    }
+
+    /// 文件类型名称
+    pub fn noun(self) -> &'static str {
+        self.noun
+    }
+
+    /// 尝试按对应格式解析文件，返回错误原因
+    fn check(self, path: &Path) -> Result<(), String> {
+        (self.parse)(&path.to_string_lossy()).map_err(|e| format!("{:#}", e))
+    }
+}
+
+/// 目录中的一项
+struct Entry {
+    name: String,
+    is_dir: bool,
+}
+
+/// 按过滤文本筛选条目，不区分大小写
+fn filter_entries<'e>(entries: &'e [Entry], filter: &str) -> Vec<&'e Entry> {
+    let filter = filter.to_lowercase();
+    entries
+        .iter()
+        .filter(|e| e.name.to_lowercase().contains(&filter))
+        .collect()
+}
+
+/// Tab补全的结果
+#[derive(Debug, PartialEq)]
+enum Completion {
+    NoMatch,        // 没有以过滤文本开头的条目
+    Dir(String),    // 唯一匹配的目录
+    Filter(String), // 补全后的过滤文本
+}
+
+/// 按前缀补全：唯一匹配的文件补全为完整文件名，多个匹配补全到公共前缀
+fn complete_name(entries: &[Entry], prefix: &str) -> Completion {
+    let prefix = prefix.to_lowercase();
+    let matches: Vec<&Entry> = entries
+        .iter()
+        .filter(|e| e.name != ".." && e.name.to_lowercase().starts_with(&prefix))
+        .collect();
+    match matches.as_slice() {
+        [] => Completion::NoMatch,
+        [entry] if entry.is_dir => Completion::Dir(entry.name.clone()),
+        [entry] => Completion::Filter(entry.name.clone()),
+        _ => {
+            let first: Vec<char> = matches[0].name.chars().collect();
+            let common = matches.iter().skip(1).fold(first.len(), |len, e| {
+                first
+                    .iter()
+                    .zip(e.name.chars())
+                    .take(len)
+                    .take_while(|(a, b)| **a == *b)
+                    .count()
+            });
+            Completion::Filter(first[..common].iter().collect())
+        }
+    }
+}
+
+/// 文件浏览器：目录导航、按名称过滤、Tab补全，选中文件时按指定格式校验
+pub struct FilePicker {
+    title: String,
+    validation: Validation,
+    dir: PathBuf,
+    entries: Vec<Entry>,
+    filter: String,
+    selected: usize,
+    message: Option<String>,
+}
+
+impl FilePicker {
+    /// 从指定路径所在的目录开始浏览
+    pub fn new(title: &str, start: &str, validation: Validation) -> Self {
+        let start = Path::new(start);
+        let dir = if start.is_dir() {
+            start.to_path_buf()
+        } else {
+            start
+                .parent()
+                .filter(|p| p.is_dir())
+                .map_or_else(|| PathBuf::from("."), Path::to_path_buf)
+        };
+        let mut picker = Self {
+            title: title.to_string(),
+            validation,
+            dir: fs::canonicalize(&dir).unwrap_or(dir),
+            entries: Vec::new(),
+            filter: String::new(),
+            selected: 0,
+            message: None,
+        };
+        picker.reload();
+        picker
+    }
+
+    /// 重新读取当前目录，目录排在文件前面
+    fn reload(&mut self) {
+        self.entries.clear();
+        if self.dir.parent().is_some() {
+            self.entries.push(Entry {
+                name: "..".to_string(),
+                is_dir: true,
+            });
+        }
+        let mut items: Vec<Entry> = fs::read_dir(&self.dir)
+            .map(|rd| {
+                rd.flatten()
+                    .map(|e| Entry {
+                        name: e.file_name().to_string_lossy().to_string(),
+                        is_dir: e.path().is_dir(),
+                    })
+                    .filter(|e| !e.name.starts_with('.'))
+                    .collect()
+            })
+            .unwrap_or_default();
+        items.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+        self.entries.extend(items);
+        self.filter.clear();
+        self.selected = 0;
+    }
+
+    /// 按过滤文本筛选后的条目
+    fn visible(&self) -> Vec<&Entry> {
+        filter_entries(&self.entries, &self.filter)
+    }
+
+    fn enter_dir(&mut self, name: &str) {
+        let target = if name == ".." {
+            self.dir.parent().map(Path::to_path_buf)
+        } else {
+            Some(self.dir.join(name))
+        };
+        if let Some(target) = target {
+            self.dir = target;
+            self.message = None;
+            self.reload();
+        }
+    }
+
+    /// Tab补全：把过滤文本补全为所有匹配项的公共前缀，唯一匹配的目录直接进入
+    fn complete(&mut self) {
+        match complete_name(&self.entries, &self.filter) {
+            Completion::NoMatch => self.message = Some("没有匹配的文件".to_string()),
+            Completion::Dir(name) => self.enter_dir(&name),
+            Completion::Filter(filter) => {
+                self.filter = filter;
+                self.selected = 0;
+            }
+        }
+    }
+
+    /// 选中文件的显示路径，位于当前工作目录下时使用相对路径
+    fn display_path(&self, name: &str) -> String {
+        let path = self.dir.join(name);
+        let cwd = std::env::current_dir()
+            .ok()
+            .and_then(|d| fs::canonicalize(d).ok());
+        cwd.and_then(|cwd| path.strip_prefix(cwd).ok().map(Path::to_path_buf))
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// 运行文件浏览器，返回选中的文件路径，取消时返回None
+    pub fn run(
+        mut self,
+        terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    ) -> Result<Option<String>> {
+        loop {
+            let visible = self.visible();
+            let selected = self.selected.min(visible.len().saturating_sub(1));
+            terminal.draw(|f| {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .margin(1)
+                    .constraints([
+                        Constraint::Length(3), // 当前目录
+                        Constraint::Length(3), // 过滤
+                        Constraint::Min(5),    // 文件列表
+                        Constraint::Length(3), // 提示
+                    ])
+                    .split(f.area());
+
+                let dir = Paragraph::new(self.dir.to_string_lossy().to_string())
+                    .block(Block::default().borders(Borders::ALL).title(self.title.as_str()));
+                f.render_widget(dir, chunks[0]);
+
+                let filter = Paragraph::new(Line::from(vec![
+                    Span::raw(self.filter.as_str()),
+                    Span::styled(" ", Style::default().bg(Color::White)),
+                ]))
+                .block(Block::default().borders(Borders::ALL).title("过滤 (Tab补全)"));
+                f.render_widget(filter, chunks[1]);
+
+                let items: Vec<ListItem> = visible
+                    .iter()
+                    .map(|e| {
+                        if e.is_dir {
+                            ListItem::new(format!("{}/", e.name))
+                                .style(Style::default().fg(Color::Cyan))
+                        } else {
+                            ListItem::new(e.name.as_str())
+                        }
+                    })
+                    .collect();
+                let list = List::new(items)
+                    .block(Block::default().borders(Borders::ALL))
+                    .highlight_style(Style::default().bg(Color::Blue).fg(Color::White))
+                    .highlight_symbol(">> ");
+                let mut state = ListState::default().with_selected(Some(selected));
+                f.render_stateful_widget(list, chunks[2], &mut state);
+
+                let help = match &self.message {
+                    Some(message) => {
+                        Paragraph::new(message.as_str()).style(Style::default().fg(Color::Red))
+                    }
+                    None => Paragraph::new(
+                        "↑/↓: 选择 | Enter: 打开/选中 | ←: 上级目录 | 输入文字: 过滤 | ESC: 取消",
+                    ),
+                };
+                f.render_widget(help.block(Block::default().borders(Borders::ALL)), chunks[3]);
+            })?;
+
+            if let Event::Key(key) = event::read()? {
+                #[cfg(windows)]
+                if key.kind != event::KeyEventKind::Press {
+                    continue;
+                }
+                let visible_len = self.visible().len();
+                match key.code {
+                    KeyCode::Up => self.selected = selected.saturating_sub(1),
+                    KeyCode::Down => {
+                        self.selected = (selected + 1).min(visible_len.saturating_sub(1))
+                    }
+                    KeyCode::Left => self.enter_dir(".."),
+                    KeyCode::Tab => self.complete(),
+                    KeyCode::Char(c) => {
+                        self.filter.push(c);
+                        self.selected = 0;
+                    }
+                    KeyCode::Backspace => {
+                        self.filter.pop();
+                        self.selected = 0;
+                    }
+                    KeyCode::Enter => {
+                        let Some(entry) = self.visible().get(selected).map(|e| (e.name.clone(), e.is_dir))
+                        else {
+                            continue;
+                        };
+                        match entry {
+                            (name, true) => self.enter_dir(&name),
+                            (name, false) => match self.validation.check(&self.dir.join(&name)) {
+                                Ok(()) => return Ok(Some(self.display_path(&name))),
+                                Err(reason) => self.message = Some(format!("{}: {}", name, reason)),
+                            },
+                        }
+                    }
+                    KeyCode::Esc => return Ok(None),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(names: &[(&str, bool)]) -> Vec<Entry> {
+        names
+            .iter()
+            .map(|&(name, is_dir)| Entry {
+                name: name.to_string(),
+                is_dir,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn completion_without_matches() {
+        let list = entries(&[("..", true), ("yustar.txt", false)]);
+        assert_eq!(complete_name(&list, "x"), Completion::NoMatch);
+        assert_eq!(complete_name(&[], ""), Completion::NoMatch);
+    }
+
+    #[test]
+    fn completion_of_a_single_match() {
+        let list = entries(&[("..", true), ("res", true), ("yustar.txt", false)]);
+        assert_eq!(
+            complete_name(&list, "Y"),
+            Completion::Filter("yustar.txt".to_string())
+        );
+        assert_eq!(
+            complete_name(&list, "r"),
+            Completion::Dir("res".to_string())
+        );
+    }
+
+    #[test]
+    fn completion_stops_at_shared_multibyte_prefix() {
+        let list = entries(&[
+            ("宇浩星陈.txt", false),
+            ("宇浩日月.txt", false),
+            ("字频.tsv", false),
+        ]);
+        assert_eq!(
+            complete_name(&list, "宇"),
+            Completion::Filter("宇浩".to_string())
+        );
+        assert_eq!(complete_name(&list, ""), Completion::Filter(String::new()));
+    }
+
+    #[test]
+    fn filter_is_case_insensitive_substring() {
+        let list = entries(&[("..", true), ("YuStar.txt", false), ("counts.txt", false)]);
+        let names: Vec<&str> = filter_entries(&list, "star")
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(names, ["YuStar.txt"]);
+        assert_eq!(filter_entries(&list, "").len(), 3);
+        assert!(filter_entries(&list, "zzz").is_empty());
+    }
+}
@@ -1,17 +1,26 @@
-use anyhow::Result;
-use crossterm::event::{self, Event, KeyCode};
+use crate::convert;
+use crate::file_picker::Validation;
+use crate::notes::Notes;
+use crate::resources::{self, Kind, Source};
+use crate::scheme::{discover_schemes, SchemeMeta};
+use crate::settings::{Field, SettingsForm};
+use anyhow::{anyhow, Context, Result};
 use rand::{seq::SliceRandom, Rng, rng};
-use ratatui::{
-    backend::CrosstermBackend,
-    layout::{Alignment, Constraint, Direction, Layout},
-    style::{Color, Style},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
-    Terminal,
-};
+use ratatui::{backend::CrosstermBackend, Terminal};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs;
 use std::io;
+use std::time::Instant;
+
+/// 自适应难度统计的滚动窗口大小
+const ROLLING_WINDOW: usize = 20;
+/// 自适应难度开始生效前至少需要的作答次数
+const ADAPTIVE_WARMUP: usize = 5;
+/// 超过该中位反应时间(毫秒)视为掌握不牢
+const SLOW_LATENCY_MS: u64 = 3000;
+/// 提示的最高级别：1高亮大码键位，2给出小码，3给出完整编码
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Radical {
@@ -20,6 +29,10 @@ pub struct Radical {
     pub frequency: usize,   // 使用频率
     pub big_code: String,   // 大码
     pub small_code: String, // 小码
+    #[serde(default)]
+    pub examples: String,   // 包含该字根的例字(编码文件的第三列)
+    #[serde(default)]
+    pub note: String,       // 字根文件中的笔记(第四列起)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,21 +44,62 @@ pub struct GameConfig {
     pub practice_mode: PracticeMode, // 练习模式
     pub order: PracticeOrder,        // 练习顺序
     pub mode: GameMode,              // 界面模式(正常/摸鱼)
+    #[serde(default = "default_weight_power")]
+    pub weight_power: f64,           // 加权随机顺序的频率指数
+    #[serde(default)]
+    pub adaptive_target: usize,      // 自适应难度的目标正确率(百分比，0为关闭)
+    #[serde(default)]
+    pub instant_feedback: bool,      // 逐键反馈并在输满编码长度时自动提交
+    #[serde(default)]
+    pub time_limit: u64,             // 限时练习的秒数(0为不限时)
     pub cancelled: bool,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+fn default_weight_power() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum PracticeMode {
-    BigCode,  // 只练习大码
-    DualCode, // 练习双编码
+    BigCode,       // 只练习大码
+    SmallCode,     // 只练习小码(大码作为已知条件给出)
+    DualCode,      // 练习双编码
+    Choice,        // 看字根选编码(四选一)
+    ReverseChoice, // 看编码选字根(四选一)
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+impl PracticeMode {
+    pub const ALL: [PracticeMode; 5] = [
+        PracticeMode::BigCode,
+        PracticeMode::SmallCode,
+        PracticeMode::DualCode,
+        PracticeMode::Choice,
+        PracticeMode::ReverseChoice,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PracticeMode::BigCode => "仅大码",
+            PracticeMode::SmallCode => "仅小码(给出大码)",
+            PracticeMode::DualCode => "大小码",
+            PracticeMode::Choice => "选择编码(四选一)",
+            PracticeMode::ReverseChoice => "选择字根(四选一)",
+        }
+    }
+
+    /// 是否为四选一模式
+    pub fn is_choice(self) -> bool {
+        matches!(self, PracticeMode::Choice | PracticeMode::ReverseChoice)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum PracticeOrder {
     Alphabetical, // 按字母顺序
     Frequency,    // 按频率顺序
     Keyboard,     // 按键盘顺序
     Random,       // 随机顺序
+    Weighted,     // 按频率加权随机
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -65,6 +119,71 @@ pub struct GameState {
     pub last_error: Option<String>,                 // 最后错误信息
     pub recent_radicals: Vec<String>,               // 最近练习的字根(最多6个)
     pub last_big_code: Option<String>,              // 上一个字根的大码(用于键盘高亮)
+    #[serde(default)]
+    pub recent_results: VecDeque<AnswerRecord>,     // 最近作答记录(用于自适应难度)
+    #[serde(default)]
+    pub seen: HashSet<String>,                      // 已经作答过的字根
+    #[serde(skip)]
+    pub shown_at: Option<Instant>,                  // 当前字根开始显示的时间
+    #[serde(default)]
+    pub choices: Vec<usize>,                        // 四选一模式的候选项(字根索引)
+    #[serde(default)]
+    pub scheme: Option<SchemeMeta>,                 // 字根文件的方案元数据
+    #[serde(default)]
+    pub undo_log: Vec<UndoRecord>,                  // 撤销过的作答记录
+    #[serde(skip)]
+    undo: Option<UndoSnapshot>,                     // 上一次作答前的状态，用于撤销
+    #[serde(skip)]
+    undo_used: bool,                                // 当前字根是否已经撤销过
+    #[serde(skip)]
+    hint_level: usize,                              // 当前字根已使用的提示级别
+    #[serde(skip)]
+    pub notes: Notes,                               // 字根笔记和键位口诀
+    #[serde(default)]
+    pub combo: usize,                               // 连续答对次数(用过提示的不算)
+    #[serde(skip)]
+    pub toast: Option<String>,                      // 新达成成就的提示，下次作答时清除
+}
+
+/// 一次被撤销的作答
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndoRecord {
+    pub radical: String, // 字根
+    pub input: String,   // 当时的输入
+    pub correct: bool,   // 撤销前的判定结果
+}
+
+/// 作答前的状态快照，撤销时恢复
+#[derive(Debug, Clone)]
+struct UndoSnapshot {
+    record: UndoRecord,
+    current_radical: usize,
+    remaining: Option<usize>, // 该字根作答前的剩余练习次数
+    correct_count: usize,
+    wrong_count: usize,
+    total_practice: usize,
+    recent_radicals: Vec<String>,
+    last_big_code: Option<String>,
+    recent_results: VecDeque<AnswerRecord>,
+    first_seen: bool, // 是否是第一次作答该字根
+    choices: Vec<usize>,
+    hint_level: usize,
+    combo: usize,
+}
+
+/// 一级提示给出的内容
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum HintStep {
+    BigKey,    // 大码键位(附带口诀和笔记)
+    SmallCode, // 小码
+    FullCode,  // 完整编码
+}
+
+/// 单次作答记录
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct AnswerRecord {
+    pub correct: bool,           // 是否正确
+    pub latency_ms: Option<u64>, // 反应时间(读档后的第一次作答没有计时)
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -76,6 +195,16 @@ struct SaveData {
     wrong_count: usize,
     total_practice: usize,
     recent_radicals: Vec<String>,
+    #[serde(default)]
+    recent_results: VecDeque<AnswerRecord>,
+    #[serde(default)]
+    seen: HashSet<String>,
+    #[serde(default)]
+    scheme: Option<SchemeMeta>,
+    #[serde(default)]
+    undo_log: Vec<UndoRecord>,
+    #[serde(default)]
+    combo: usize,
     config: GameConfig,
 }
 
@@ -89,6 +218,11 @@ impl GameState {
             wrong_count: self.wrong_count,
             total_practice: self.total_practice,
             recent_radicals: self.recent_radicals.clone(),
+            recent_results: self.recent_results.clone(),
+            seen: self.seen.clone(),
+            scheme: self.scheme.clone(),
+            undo_log: self.undo_log.clone(),
+            combo: self.combo,
             config: config.clone(),
         };
 
@@ -111,6 +245,18 @@ impl GameState {
                         last_error: None,
                         recent_radicals: save_data.recent_radicals,
                         last_big_code: None,
+                        recent_results: save_data.recent_results,
+                        seen: save_data.seen,
+                        shown_at: None,
+                        choices: Vec::new(),
+                        scheme: save_data.scheme,
+                        undo_log: save_data.undo_log,
+                        undo: None,
+                        undo_used: false,
+                        hint_level: 0,
+                        notes: Notes::default(),
+                        combo: save_data.combo,
+                        toast: None,
                     },
                     save_data.config,
                 ));
@@ -125,7 +271,6 @@ impl GameConfig {
     pub fn show_settings_menu(
         terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     ) -> Result<Self> {
-        let mut selected_item = 0;
         let mut config = Self {
             radical_file: "res/yujoy-3.8.0.txt".to_string(),
             frequency_file: "res/counts.txt".to_string(),
@@ -134,360 +279,297 @@ impl GameConfig {
             practice_mode: PracticeMode::DualCode,
             order: PracticeOrder::Random,
             mode: GameMode::Normal,
+            weight_power: default_weight_power(),
+            adaptive_target: 0,
+            instant_feedback: false,
+            time_limit: 0,
             cancelled: false,
         };
 
-        loop {
-            terminal.draw(|f| {
-                let size = f.area();
-                let chunks = Layout::default()
-                    .direction(Direction::Vertical)
-                    .margin(1)
-                    .constraints([
-                        Constraint::Length(3),
-                        Constraint::Min(8),
-                        Constraint::Length(3),
-                    ])
-                    .split(size);
-
-                // 标题
-                let title = Paragraph::new("设置菜单")
-                    .block(Block::default().borders(Borders::ALL))
-                    .alignment(Alignment::Center);
-                f.render_widget(title, chunks[0]);
-
-                // 设置选项
-                let settings_items = vec![
-                    ListItem::new(format!("字根文件: {}", config.radical_file)),
-                    ListItem::new(format!("频率文件: {}", config.frequency_file)),
-                    ListItem::new(format!("错误惩罚: {}次", config.penalty)),
-                    ListItem::new(format!("最少练习: {}次", config.min_practice_count)),
-                    ListItem::new(format!(
-                        "练习模式: {}",
-                        match config.practice_mode {
-                            PracticeMode::BigCode => "仅大码",
-                            PracticeMode::DualCode => "大小码",
-                        }
-                    )),
-                    ListItem::new(format!(
-                        "练习顺序: {}",
-                        match config.order {
-                            PracticeOrder::Alphabetical => "字母顺序",
-                            PracticeOrder::Frequency => "频率顺序",
-                            PracticeOrder::Keyboard => "键盘顺序",
-                            PracticeOrder::Random => "随机顺序",
-                        }
-                    )),
-                    ListItem::new(format!(
-                        "界面模式: {}",
-                        match config.mode {
-                            GameMode::Normal => "正常模式",
-                            GameMode::Pretend => "摸鱼模式(界面空白区域使用随机字符填充)",
-                        }
-                    )),
-                ];
-
-                let mut state = ListState::default();
-                state.select(Some(selected_item));
-                let settings_list = List::new(settings_items)
-                    .block(Block::default().borders(Borders::ALL).title("设置选项"))
-                    .highlight_style(Style::default().bg(Color::Blue).fg(Color::White))
-                    .highlight_symbol(">> ");
-                f.render_stateful_widget(settings_list, chunks[1], &mut state);
-
-                // 操作提示
-                let help =
-                    Paragraph::new("↑/↓: 选择选项 | ←/→: 修改选项 | Enter: 确认 | ESC: 取消")
-                        .block(Block::default().borders(Borders::ALL))
-                        .alignment(Alignment::Center);
-                f.render_widget(help, chunks[2]);
-            })?;
-
-            if let Event::Key(key) = event::read()? {
-                #[cfg(windows)]
-                if key.kind != event::KeyEventKind::Press {
-                    continue;
-                }
-                match key.code {
-                    KeyCode::Up => {
-                        if selected_item > 0 {
-                            selected_item -= 1;
-                        }
-                    }
-                    KeyCode::Down => {
-                        if selected_item < 6 {
-                            selected_item += 1;
-                        }
+        // 扫描内置的、res目录和用户数据目录中的资源文件，字根文件自动配对频率文件
+        let resource_list = resources::list();
+        let schemes = discover_schemes(&resource_list);
+        let builtin_only: HashSet<String> = resource_list
+            .iter()
+            .filter(|r| r.source == Source::Builtin)
+            .map(|r| r.path.clone())
+            .collect();
+        let counts_files: Vec<String> = resource_list
+            .iter()
+            .filter(|r| r.kind == Kind::Counts)
+            .map(|r| r.path.clone())
+            .collect();
+
+        // 手动输入的字根文件的方案名称，按路径缓存避免每次重绘都读文件
+        let scheme_labels: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+
+        let fields = vec![
+            Field::path(
+                "字根方案",
+                "在已发现的方案间切换，会同时换上配对的频率文件；最右侧可打开文件浏览器手动选择字根文件",
+                schemes.iter().map(|s| s.radical_file.clone()).collect(),
+                Radical::code_file_validation(),
+                |c: &Self| c.radical_file.clone(),
+                |c: &mut Self, path| {
+                    // 切换方案时同时换上配对的频率文件
+                    if let Some(scheme) = schemes.iter().find(|s| s.radical_file == path) {
+                        c.frequency_file = scheme.frequency_file.clone();
                     }
-                    KeyCode::Left | KeyCode::Right => {
-                        match selected_item {
-                            0 => {
-                                let files = [
-                                    "res/yujoy-3.8.0.txt",
-                                    "res/yulight-3.8.0.txt",
-                                    "res/yustar-3.8.0.txt",
-                                    "res/yujoy-3.6.0.txt",
-                                    "res/yusm-3.9.0-20250522.txt",
-                                    "按右方向键手动输入→",
-                                ];
-                                let current_idx = files
-                                    .iter()
-                                    .position(|&f| f == config.radical_file.as_str())
-                                    .unwrap_or(files.len() - 1);
-
-                                match key.code {
-                                    KeyCode::Left => {
-                                        if current_idx > 0 {
-                                            config.radical_file =
-                                                files[current_idx - 1].to_string();
-                                        } else if current_idx == 0 {
-                                            config.radical_file =
-                                                files[files.len() - 1].to_string();
-                                        }
-
-                                        // 从手动输入切换回文件时清除输入内容
-                                        if current_idx == files.len() - 1 {
-                                            config.radical_file =
-                                                files[files.len() - 2].to_string();
-                                        }
-                                    }
-                                    KeyCode::Right => {
-                                        if current_idx < files.len() - 1 {
-                                            config.radical_file =
-                                                files[current_idx + 1].to_string();
-                                        } else if current_idx == files.len() - 1 {
-                                            // 进入手动输入模式
-                                            let mut input = String::new();
-                                            terminal.draw(|f| {
-                                                let size = f.area();
-                                                let block = Block::default()
-                                                    .title("输入字根文件路径 (Enter确认, ESC取消)")
-                                                    .borders(Borders::ALL);
-                                                let input_text = Paragraph::new(input.as_str())
-                                                    .block(block)
-                                                    .alignment(Alignment::Center);
-                                                f.render_widget(input_text, size);
-                                            })?;
-
-                                            loop {
-                                                if let Event::Key(key) = event::read()? {
-                                                    #[cfg(windows)]
-                                                    if key.kind
-                                                        != event::KeyEventKind::Press
-                                                    {
-                                                        continue;
-                                                    }
-                                                    match key.code {
-                                                        KeyCode::Char(c) => {
-                                                            input.push(c);
-                                                        }
-                                                        KeyCode::Backspace => {
-                                                            input.pop();
-                                                        }
-                                                        KeyCode::Enter => {
-                                                            if !input.is_empty() {
-                                                                config.radical_file =
-                                                                    input.trim().to_string();
-                                                                break;
-                                                            }
-                                                        }
-                                                        KeyCode::Esc => {
-                                                            config.radical_file =
-                                                                files[files.len() - 2].to_string();
-                                                            break;
-                                                        }
-                                                        _ => {}
-                                                    }
-                                                }
-                                                terminal.draw(|f| {
-                                                    let size = f.area();
-                                                    let block = Block::default()
-                                                        .title("输入字根文件路径 (Enter确认, ESC取消)")
-                                                        .borders(Borders::ALL);
-                                                    let input_text = Paragraph::new(input.as_str())
-                                                        .block(block)
-                                                        .alignment(Alignment::Center);
-                                                    f.render_widget(input_text, size);
-                                                })?;
-                                            }
-                                        }
-                                    }
-                                    _ => {}
-                                }
-                            }
-                            1 => {
-                                // 频率文件逻辑（与字根文件类似）
-                                let files = [
-                                    "res/counts.txt",
-                                    "res/counts-3.6.0.txt",
-                                    "按右方向键手动输入→",
-                                ];
-                                let current_idx = files
-                                    .iter()
-                                    .position(|&f| f == config.frequency_file.as_str())
-                                    .unwrap_or(files.len() - 1);
-
-                                match key.code {
-                                    KeyCode::Left => {
-                                        if current_idx > 0 {
-                                            config.frequency_file =
-                                                files[current_idx - 1].to_string();
-                                        } else if current_idx == 0 {
-                                            config.frequency_file =
-                                                files[files.len() - 1].to_string();
-                                        }
-                                        if current_idx == files.len() - 1 {
-                                            config.frequency_file =
-                                                files[files.len() - 2].to_string();
-                                        }
-                                    }
-                                    KeyCode::Right => {
-                                        if current_idx < files.len() - 1 {
-                                            config.frequency_file =
-                                                files[current_idx + 1].to_string();
-                                        } else if current_idx == files.len() - 1 {
-                                            // 手动输入
-                                            let mut input = String::new();
-                                            terminal.draw(|f| {
-                                                let size = f.area();
-                                                let block = Block::default()
-                                                    .title("输入频率文件路径 (Enter确认, ESC取消)")
-                                                    .borders(Borders::ALL);
-                                                let input_text = Paragraph::new(input.as_str())
-                                                    .block(block)
-                                                    .alignment(Alignment::Center);
-                                                f.render_widget(input_text, size);
-                                            })?;
-                                            loop {
-                                                if let Event::Key(key) = event::read()? {
-                                                    #[cfg(windows)]
-                                                    if key.kind
-                                                        != event::KeyEventKind::Press
-                                                    {
-                                                        continue;
-                                                    }
-                                                    match key.code {
-                                                        KeyCode::Char(c) => input.push(c),
-                                                        KeyCode::Backspace => {
-                                                            input.pop();
-                                                        }
-                                                        KeyCode::Enter => {
-                                                            if !input.is_empty() {
-                                                                config.frequency_file =
-                                                                    input.trim().to_string();
-                                                                break;
-                                                            }
-                                                        }
-                                                        KeyCode::Esc => {
-                                                            config.frequency_file =
-                                                                files[files.len() - 2].to_string();
-                                                            break;
-                                                        }
-                                                        _ => {}
-                                                    }
-                                                }
-                                                terminal.draw(|f| {
-                                                    let size = f.area();
-                                                    let block = Block::default()
-                                                        .title("输入频率文件路径 (Enter确认, ESC取消)")
-                                                        .borders(Borders::ALL);
-                                                    let input_text = Paragraph::new(input.as_str())
-                                                        .block(block)
-                                                        .alignment(Alignment::Center);
-                                                    f.render_widget(input_text, size);
-                                                })?;
-                                            }
-                                        }
-                                    }
-                                    _ => {}
-                                }
-                            }
-                            2 => {
-                                config.penalty = match (config.penalty, key.code) {
-                                    (1, KeyCode::Left) => 1,
-                                    (n, KeyCode::Left) => n - 1,
-                                    (10, KeyCode::Right) => 10,
-                                    (n, KeyCode::Right) => n + 1,
-                                    _ => config.penalty,
-                                }
-                            }
-                            3 => {
-                                config.min_practice_count = match (config.min_practice_count, key.code) {
-                                    (1, KeyCode::Left) => 1,
-                                    (n, KeyCode::Left) => n - 1,
-                                    (5, KeyCode::Right) => 5,
-                                    (n, KeyCode::Right) => n + 1,
-                                    _ => config.min_practice_count,
-                                }
-                            }
-                            4 => {
-                                config.practice_mode = match key.code {
-                                    KeyCode::Left => match &config.practice_mode {
-                                        PracticeMode::BigCode => PracticeMode::BigCode,
-                                        PracticeMode::DualCode => PracticeMode::BigCode,
-                                    },
-                                    KeyCode::Right => match &config.practice_mode {
-                                        PracticeMode::BigCode => PracticeMode::DualCode,
-                                        PracticeMode::DualCode => PracticeMode::DualCode,
-                                    },
-                                    _ => config.practice_mode,
-                                }
-                            }
-                            5 => {
-                                config.order = match key.code {
-                                    KeyCode::Left => match &config.order {
-                                        PracticeOrder::Alphabetical => PracticeOrder::Alphabetical,
-                                        PracticeOrder::Frequency => PracticeOrder::Alphabetical,
-                                        PracticeOrder::Keyboard => PracticeOrder::Frequency,
-                                        PracticeOrder::Random => PracticeOrder::Keyboard,
-                                    },
-                                    KeyCode::Right => match &config.order {
-                                        PracticeOrder::Alphabetical => PracticeOrder::Frequency,
-                                        PracticeOrder::Frequency => PracticeOrder::Keyboard,
-                                        PracticeOrder::Keyboard => PracticeOrder::Random,
-                                        PracticeOrder::Random => PracticeOrder::Random,
-                                    },
-                                    _ => config.order,
-                                }
-                            }
-                            6 => {
-                                config.mode = match key.code {
-                                    KeyCode::Left => match &config.mode {
-                                        GameMode::Normal => GameMode::Normal,
-                                        GameMode::Pretend => GameMode::Normal,
-                                    },
-                                    KeyCode::Right => match &config.mode {
-                                        GameMode::Normal => GameMode::Pretend,
-                                        GameMode::Pretend => GameMode::Pretend,
-                                    },
-                                    _ => config.mode,
-                                }
-                            }
-                            _ => {}
-                        }
+                    c.radical_file = path;
+                },
+                |path| {
+                    scheme_labels
+                        .borrow_mut()
+                        .entry(path.to_string())
+                        .or_insert_with(|| {
+                            let shown = if builtin_only.contains(path) {
+                                format!("{}, 内置", path)
+                            } else {
+                                path.to_string()
+                            };
+                            let label = schemes
+                                .iter()
+                                .find(|s| s.radical_file == path)
+                                .map(|s| s.label.clone())
+                                .or_else(|| {
+                                    SchemeMeta::read_from_file(path).map(|meta| meta.display_name())
+                                });
+                            label.map_or_else(|| shown.clone(), |label| format!("{} ({})", label, shown))
+                        })
+                        .clone()
+                },
+            ),
+            Field::path(
+                "频率文件",
+                "字根使用频率，影响频率顺序、加权随机和反馈中的频率排名",
+                counts_files,
+                Radical::frequency_file_validation(),
+                |c: &Self| c.frequency_file.clone(),
+                |c: &mut Self, path| c.frequency_file = path,
+                |path| {
+                    if builtin_only.contains(path) {
+                        format!("{} (内置)", path)
+                    } else {
+                        path.to_string()
                     }
-                    KeyCode::Enter => {
-                        return Ok(config);
+                },
+            ),
+            Field::range(
+                "错误惩罚",
+                "答错一个字根后需要额外答对的次数；开启自适应难度时自动调整",
+                (1.0, 10.0, 1.0),
+                |c: &Self| c.penalty as f64,
+                |c: &mut Self, v| c.penalty = v as usize,
+                |c: &Self, v| {
+                    if c.adaptive_target > 0 {
+                        "自动调整".to_string()
+                    } else {
+                        format!("{}次", v)
                     }
-                    KeyCode::Esc => {
-                        config.cancelled = true;
-                        return Ok(config)
+                },
+            ),
+            Field::range(
+                "最少练习",
+                "每个字根至少需要答对的次数",
+                (1.0, 5.0, 1.0),
+                |c: &Self| c.min_practice_count as f64,
+                |c: &mut Self, v| c.min_practice_count = v as usize,
+                |_, v| format!("{}次", v),
+            ),
+            Field::choice(
+                "练习模式",
+                "仅小码模式会给出大码；四选一模式按数字键直接作答",
+                PracticeMode::ALL.iter().map(|&m| (m, m.label())).collect(),
+                |c: &Self| c.practice_mode,
+                |c: &mut Self, v| c.practice_mode = v,
+            ),
+            Field::choice(
+                "练习顺序",
+                "加权随机按使用频率随机抽取，高频字根更早出现",
+                vec![
+                    (PracticeOrder::Alphabetical, "字母顺序"),
+                    (PracticeOrder::Frequency, "频率顺序"),
+                    (PracticeOrder::Keyboard, "键盘顺序"),
+                    (PracticeOrder::Random, "随机顺序"),
+                    (PracticeOrder::Weighted, "加权随机"),
+                ],
+                |c: &Self| c.order,
+                |c: &mut Self, v| c.order = v,
+            ),
+            Field::choice(
+                "界面模式",
+                "摸鱼模式下界面空白区域使用随机字符填充",
+                vec![
+                    (GameMode::Normal, "正常模式"),
+                    (GameMode::Pretend, "摸鱼模式(界面空白区域使用随机字符填充)"),
+                ],
+                |c: &Self| c.mode,
+                |c: &mut Self, v| c.mode = v,
+            ),
+            Field::range(
+                "加权指数",
+                "仅对加权随机顺序生效，指数越大越偏向高频字根",
+                (0.25, 3.0, 0.25),
+                |c: &Self| c.weight_power,
+                |c: &mut Self, v| c.weight_power = v,
+                |_, v| format!("{:.2}", v),
+            ),
+            Field::choice(
+                "自适应难度",
+                format!(
+                    "根据最近{}次作答的正确率和反应时间自动调整错误惩罚，掌握不牢时暂停引入新字根",
+                    ROLLING_WINDOW
+                ),
+                [0].into_iter()
+                    .chain((70..=95).step_by(5))
+                    .map(|n| {
+                        let label = if n == 0 {
+                            "关闭".to_string()
+                        } else {
+                            format!("目标正确率{}%", n)
+                        };
+                        (n, label)
+                    })
+                    .collect(),
+                |c: &Self| c.adaptive_target,
+                |c: &mut Self, v| c.adaptive_target = v,
+            ),
+            Field::toggle(
+                "逐键反馈",
+                "输入时逐个字符标出对错，输满编码长度后自动提交",
+                ("开启(输满自动提交)", "关闭(Enter提交)"),
+                |c: &Self| c.instant_feedback,
+                |c: &mut Self, v| c.instant_feedback = v,
+            ),
+            Field::choice(
+                "限时练习",
+                "在限定时间内尽量多答对，得分为答对次数减去答错次数，成绩计入排行榜(欢迎界面按L查看)",
+                [0, 60, 120, 180, 300]
+                    .into_iter()
+                    .map(|n| {
+                        let label = if n == 0 {
+                            "关闭".to_string()
+                        } else {
+                            format!("{}分钟", n / 60)
+                        };
+                        (n, label)
+                    })
+                    .collect(),
+                |c: &Self| c.time_limit,
+                |c: &mut Self, v| c.time_limit = v,
+            ),
+        ];
+
+        let confirmed = SettingsForm::new("设置菜单", fields).run(terminal, &mut config)?;
+        config.cancelled = !confirmed;
+        Ok(config)
+    }
+}
+
+/// 部件名中的位置后缀，如{奉下}表示“奉”的下部
+const PART_POSITIONS: &[(&str, &str)] = &[
+    ("上", "上部"),
+    ("下", "下部"),
+    ("左", "左部"),
+    ("右", "右部"),
+    ("中", "中部"),
+    ("心", "内部"),
+    ("框", "外框"),
+];
+
+/// 是否为私用区字符，多数终端字体无法显示
+fn is_private_use(c: char) -> bool {
+    matches!(c as u32, 0xE000..=0xF8FF | 0xF0000..=0xFFFFD | 0x100000..=0x10FFFD)
+}
+
+impl Radical {
+    /// 终端中显示的字根文字
+    ///
+    /// {横日}式的部件名附上描述，私用区字符换成码位，并都附上几个例字，
+    /// 如"{奉下}(“奉”的下部，例字: 奉 捧)"
+    pub fn display_text(&self) -> String {
+        let mut examples: Vec<char> = self.examples.chars().collect();
+        let (shown, description) =
+            if let Some(name) = self.text.strip_prefix('{').and_then(|t| t.strip_suffix('}')) {
+                let mut chars = name.chars();
+                let base = chars.next();
+                let rest = chars.as_str();
+                let position = PART_POSITIONS.iter().find(|(suffix, _)| *suffix == rest);
+                match (base, position) {
+                    (Some(base), Some((_, position))) => {
+                        // 部件名中的汉字本身就包含该部件
+                        if !examples.contains(&base) {
+                            examples.insert(0, base);
+                        }
+                        (self.text.clone(), format!("“{}”的{}", base, position))
                     }
-                    _ => {}
+                    _ => (self.text.clone(), format!("部件“{}”", name)),
                 }
-            }
+            } else if self.text.chars().any(is_private_use) {
+                let code_points: Vec<String> = self
+                    .text
+                    .chars()
+                    .map(|c| format!("U+{:04X}", c as u32))
+                    .collect();
+                // 没有例字时附上字符本身，装有宇浩字体的终端仍能看到字形
+                let description = if examples.is_empty() {
+                    format!("私用区字符“{}”，需要宇浩字体显示", self.text)
+                } else {
+                    "私用区字符".to_string()
+                };
+                (code_points.join(" "), description)
+            } else {
+                return self.text.clone();
+            };
+        examples.truncate(3);
+        if examples.is_empty() {
+            return format!("{}({})", shown, description);
         }
+        let examples: Vec<String> = examples.iter().map(char::to_string).collect();
+        format!("{}({}，例字: {})", shown, description, examples.join(" "))
+    }
+
+    /// 例字提示，如"甫: 捕 铺 辅"，没有例字时返回None
+    pub fn examples_line(&self) -> Option<String> {
+        if self.examples.is_empty() {
+            return None;
+        }
+        let chars: Vec<String> = self.examples.chars().map(String::from).collect();
+        Some(format!("{}: {}", self.text, chars.join(" ")))
+    }
+
+    /// 带大小写区分的编码显示，如"Af"
+    pub fn display_code(&self) -> String {
+        format!(
+            "{}{}",
+            self.big_code.to_uppercase(),
+            self.small_code.to_lowercase()
+        )
+    }
+
+    /// 设置中选择字根文件时的校验，与启动时使用相同的加载逻辑
+    pub fn code_file_validation() -> Validation {
+        Validation::new("字根文件", |path| {
+            Self::load_code_data(path).map(|_| ())
+        })
+    }
+
+    /// 设置中选择频率文件时的校验
+    pub fn frequency_file_validation() -> Validation {
+        Validation::new("频率文件", |path| {
+            Self::load_frequency_data(path).map(|_| ())
+        })
     }
-}
 
-impl Radical {
     /// 从文件加载字根数据
     pub fn load_from_files(counts_file: &str, code_file: &str) -> Result<Vec<Self>> {
         // 加载字根频率数据
-        let frequency_map = Self::load_frequency_data(counts_file)?;
+        let frequency_map = Self::load_frequency_data(counts_file)
+            .with_context(|| format!("无法加载频率文件 {}", counts_file))?;
 
         // 加载字根编码数据
-        let mut radicals = Self::load_code_data(code_file)?;
+        let mut radicals = Self::load_code_data(code_file)
+            .with_context(|| format!("无法加载字根文件 {}", code_file))?;
 
         // 合并频率数据
         for radical in &mut radicals {
@@ -496,53 +578,89 @@ impl Radical {
             }
         }
 
+        // 字根文件没有例字列时(如内置的字根文件)，从同一方案版本的拆分表中挑选例字
+        if radicals.iter().all(|r| r.examples.is_empty()) {
+            let examples = SchemeMeta::read_from_file(code_file)
+                .and_then(|meta| convert::find_examples(&meta))
+                .unwrap_or_default();
+            for radical in &mut radicals {
+                if let Some(chars) = examples.get(&radical.text) {
+                    radical.examples = chars.clone();
+                }
+            }
+        }
+
         Ok(radicals)
     }
 
     fn load_frequency_data(path: &str) -> Result<HashMap<String, usize>> {
-        let content = fs::read_to_string(path)?;
+        let content = resources::read(path)?;
         let mut map = HashMap::new();
 
-        for line in content.lines() {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 2 {
-                let text = parts[0].to_string();
-                let freq = parts[1].parse().unwrap_or(0);
-                map.insert(text, freq);
+        for (idx, line) in content.lines().enumerate() {
+            // 文件开头的#注释行是方案元数据
+            if line.starts_with('#') || line.trim().is_empty() {
+                continue;
             }
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let [text, freq, ..] = parts.as_slice() else {
+                return Err(anyhow!("第{}行不是\"字根 次数\"格式: {}", idx + 1, line));
+            };
+            let freq = freq
+                .parse()
+                .map_err(|_| anyhow!("第{}行的次数不是数字: {}", idx + 1, line))?;
+            map.insert(text.to_string(), freq);
         }
 
         Ok(map)
     }
 
     fn load_code_data(path: &str) -> Result<Vec<Self>> {
-        let content = fs::read_to_string(path)?;
+        let content = resources::read(path)?;
         let mut radicals = Vec::new();
 
-        for line in content.lines() {
+        for (idx, line) in content.lines().enumerate() {
+            if line.starts_with('#') || line.trim().is_empty() {
+                continue;
+            }
             let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 2 {
-                let code = parts[0].to_string();
-                let text = parts[1].to_string();
-
-                // 大码是第一个字符，小码是剩余字符
-                let big_code = code.chars().next().unwrap().to_string();
-                let small_code = if code.len() > 1 {
-                    code.chars().skip(1).collect()
-                } else {
-                    "".to_string()
-                };
-
-                radicals.push(Self {
-                    code,
-                    text,
-                    frequency: 0, // 初始化为0，后面会合并频率数据
-                    big_code,
-                    small_code,
-                });
+            if parts.len() < 2 {
+                return Err(anyhow!("第{}行不是\"编码 字根\"格式: {}", idx + 1, line));
+            }
+            let code = parts[0].to_string();
+            if !code.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(anyhow!("第{}行的编码不是字母: {}", idx + 1, line));
             }
+            let text = parts[1].to_string();
+            // 第三列是例字("-"表示没有)，之后的内容都是笔记
+            let examples = match parts.get(2) {
+                Some(&"-") | None => String::new(),
+                Some(e) => e.to_string(),
+            };
+            let note = parts.get(3..).map_or(String::new(), |p| p.join(" "));
+
+            // 大码是第一个字符，小码是剩余字符
+            let big_code = code.chars().next().unwrap().to_string();
+            let small_code = if code.len() > 1 {
+                code.chars().skip(1).collect()
+            } else {
+                "".to_string()
+            };
+
+            radicals.push(Self {
+                code,
+                text,
+                frequency: 0, // 初始化为0，后面会合并频率数据
+                big_code,
+                small_code,
+                examples,
+                note,
+            });
         }
 
+        if radicals.is_empty() {
+            return Err(anyhow!("没有字根编码数据"));
+        }
         Ok(radicals)
     }
 }
@@ -557,7 +675,7 @@ impl GameState {
                 radicals
             }
             PracticeOrder::Frequency => {
-                radicals.sort_by(|a, b| b.frequency.cmp(&a.frequency));
+                radicals.sort_by_key(|r| std::cmp::Reverse(r.frequency));
                 radicals
             }
             PracticeOrder::Keyboard => {
@@ -590,6 +708,11 @@ impl GameState {
                 radicals.shuffle(&mut rng);
                 radicals
             }
+            PracticeOrder::Weighted => {
+                let indices = (0..radicals.len()).collect();
+                let order = weighted_shuffle(&radicals, indices, config.weight_power);
+                order.into_iter().map(|i| radicals[i].clone()).collect()
+            }
         };
 
         // 初始化每个字根的练习次数
@@ -598,7 +721,7 @@ impl GameState {
             remaining_practice.insert(radical.text.clone(), config.min_practice_count);
         }
 
-        GameState {
+        let mut state = GameState {
             radicals,
             current_radical: 0,
             remaining_practice,
@@ -608,7 +731,21 @@ impl GameState {
             last_error: None,
             recent_radicals: Vec::with_capacity(6), // 预分配容量为6以适应随机间隔
             last_big_code: None,
-        }
+            recent_results: VecDeque::with_capacity(ROLLING_WINDOW),
+            seen: HashSet::new(),
+            shown_at: Some(Instant::now()),
+            choices: Vec::new(),
+            scheme: None,
+            undo_log: Vec::new(),
+            undo: None,
+            undo_used: false,
+            hint_level: 0,
+            notes: Notes::default(),
+            combo: 0,
+            toast: None,
+        };
+        state.refresh_choices(config);
+        state
     }
 
     /// 获取当前练习的字根
@@ -628,7 +765,7 @@ impl GameState {
         };
         // 计算排名
         let mut sorted = self.radicals.clone();
-        sorted.sort_by(|a, b| b.frequency.cmp(&a.frequency));
+        sorted.sort_by_key(|r| std::cmp::Reverse(r.frequency));
         let rank = sorted
             .iter()
             .position(|r| r.text == radical.text)
@@ -691,9 +828,55 @@ impl GameState {
 
         // 安全比较输入（不区分大小写）
         let input_lower = input.to_lowercase();
+        let code_lower = radical.code.to_lowercase();
+        let big_lower = radical.big_code.to_lowercase();
+        let small_lower = radical.small_code.to_lowercase();
+        // 记录答错的部分，用于提示
+        let mut wrong_part = None;
         let is_correct = match config.practice_mode {
-            PracticeMode::BigCode => input_lower == radical.big_code.to_lowercase(),
-            PracticeMode::DualCode => input_lower == radical.code.to_lowercase(),
+            PracticeMode::BigCode => input_lower == big_lower,
+            PracticeMode::SmallCode => {
+                // 只需输入小码；输入完整编码时只看小码部分(大码已给出)
+                let typed_small = if input_lower.chars().count() == code_lower.chars().count()
+                    && input_lower.starts_with(&big_lower)
+                {
+                    &input_lower[big_lower.len()..]
+                } else {
+                    input_lower.as_str()
+                };
+                let correct = if small_lower.is_empty() {
+                    input_lower == code_lower
+                } else {
+                    typed_small == small_lower
+                };
+                if !correct {
+                    wrong_part = Some("小码错误");
+                }
+                correct
+            }
+            PracticeMode::DualCode => {
+                let correct = input_lower == code_lower;
+                if !correct {
+                    let big_ok = input_lower.starts_with(&big_lower);
+                    let small_ok = input_lower.get(big_lower.len()..) == Some(small_lower.as_str());
+                    wrong_part = Some(match (big_ok, small_ok) {
+                        (true, _) => "小码错误",
+                        (false, true) => "大码错误",
+                        (false, false) => "大小码均错误",
+                    });
+                }
+                correct
+            }
+            PracticeMode::Choice | PracticeMode::ReverseChoice => {
+                let Some(chosen) = self.chosen_radical(input) else {
+                    return (false, Some("请按数字键1-4选择".to_string()));
+                };
+                if config.practice_mode == PracticeMode::Choice {
+                    chosen.code.to_lowercase() == radical.code.to_lowercase()
+                } else {
+                    chosen.text == radical.text
+                }
+            }
         };
 
         // 获取频率数据
@@ -701,16 +884,26 @@ impl GameState {
 
         // 生成纯文本提示信息
         let status = if is_correct { "正确" } else { "错误" };
-        let message = format!(
-            "【{}】“{}”的编码是:{}{}，使用频率为:{}({:.4}‰)，排在第{}位",
+        let part = wrong_part.map_or(String::new(), |p| format!("{}，", p));
+        let mut message = format!(
+            "【{}】{}“{}”的编码是:{}，使用频率为:{}({:.4}‰)，排在第{}位",
             status,
-            radical.text,
-            radical.big_code.to_uppercase(),
-            radical.small_code.to_lowercase(),
+            part,
+            radical.display_text(),
+            radical.display_code(),
             count,
             percentage,
             rank
         );
+        if let Some(examples) = radical.examples_line() {
+            message.push('\n');
+            message.push_str(&examples);
+        }
+        // 答错时附上口诀和笔记
+        if let Some(note) = self.notes.describe(radical).filter(|_| !is_correct) {
+            message.push('\n');
+            message.push_str(&note);
+        }
 
         (is_correct, Some(message))
     }
@@ -724,15 +917,44 @@ impl GameState {
             return false;
         }
 
+        // 四选一模式下无效的选项不计入作答
+        if config.practice_mode.is_choice() && self.chosen_radical(input).is_none() {
+            self.last_error = Some("请按数字键1-4选择".to_string());
+            return false;
+        }
+
         let (is_correct, message) = self.check_input_core(input, config);
         self.last_error = message;
 
         // 获取当前字根文本
-        let current_radical = self.current_radical();
-        let current_radical_text = current_radical.map(|r| r.text.clone());
+        let current_radical_text = self.current_radical().map(|r| r.text.clone());
+
+        // 记录作答前的状态，每个字根只能撤销一次
+        self.undo = match &current_radical_text {
+            Some(text) if !self.undo_used => Some(UndoSnapshot {
+                record: UndoRecord {
+                    radical: text.clone(),
+                    input: input.to_string(),
+                    correct: is_correct,
+                },
+                current_radical: self.current_radical,
+                remaining: self.remaining_practice.get(text).copied(),
+                correct_count: self.correct_count,
+                wrong_count: self.wrong_count,
+                total_practice: self.total_practice,
+                recent_radicals: self.recent_radicals.clone(),
+                last_big_code: self.last_big_code.clone(),
+                recent_results: self.recent_results.clone(),
+                first_seen: !self.seen.contains(text),
+                choices: self.choices.clone(),
+                hint_level: self.hint_level,
+                combo: self.combo,
+            }),
+            _ => None,
+        };
 
         // 更新上一个字根的大码
-        if let Some(radical) = current_radical {
+        if let Some(radical) = self.current_radical() {
             self.last_big_code = Some(radical.big_code.clone());
         }
 
@@ -744,25 +966,263 @@ impl GameState {
             }
         }
 
+        // 记录作答结果，供自适应难度使用(用过提示的不算答对)
+        let latency_ms = self.shown_at.map(|t| t.elapsed().as_millis() as u64);
+        let clean_correct = is_correct && self.hint_level == 0;
+        self.recent_results.push_back(AnswerRecord {
+            correct: clean_correct,
+            latency_ms,
+        });
+        self.combo = if clean_correct { self.combo + 1 } else { 0 };
+        if self.recent_results.len() > ROLLING_WINDOW {
+            self.recent_results.pop_front();
+        }
+        self.shown_at = Some(Instant::now());
+        let penalty = self.effective_penalty(config);
+
         // 更新状态（摸鱼模式和正常模式都更新）
         if let Some(text) = current_radical_text {
+            self.seen.insert(text.clone());
             if is_correct {
                 self.correct_count += 1;
+                // 用过提示时每级提示按部分答错计入惩罚
+                let hint_penalty = self.hint_penalty(penalty, config);
                 self.remaining_practice
                     .entry(text)
-                    .and_modify(|c| *c = c.saturating_sub(1));
+                    .and_modify(|c| *c = c.saturating_sub(1) + hint_penalty);
+                if hint_penalty > 0 {
+                    if let Some(message) = &mut self.last_error {
+                        // 附在第一行末尾，第二行是例字
+                        let note = format!("(使用了提示，额外练习{}次)", hint_penalty);
+                        let end = message.find('\n').unwrap_or(message.len());
+                        message.insert_str(end, &note);
+                    }
+                }
             } else {
                 self.wrong_count += 1;
                 self.remaining_practice
                     .entry(text)
-                    .and_modify(|c| *c += config.penalty);
+                    .and_modify(|c| *c += penalty);
             }
             self.total_practice += 1;
+            self.hint_level = 0;
+        }
+
+        // 答错后重新出选项，避免靠排除法作答
+        if !is_correct {
+            self.refresh_choices(config);
         }
 
         is_correct
     }
 
+    /// 把字根移出本次练习(已掌握或暂停)，当前字根被移出时切换到下一个
+    pub fn exclude<'a>(&mut self, texts: impl IntoIterator<Item = &'a String>, config: &GameConfig) {
+        for text in texts {
+            self.remaining_practice.remove(text);
+        }
+        // 撤销会恢复被移出字根的练习次数，移出后不再允许撤销
+        self.undo = None;
+        let current_excluded = self
+            .current_radical()
+            .is_some_and(|r| !self.remaining_practice.contains_key(&r.text));
+        if current_excluded {
+            self.next_radical(config);
+        }
+    }
+
+    /// 是否可以撤销上一次作答
+    pub fn can_undo(&self) -> bool {
+        self.undo.is_some()
+    }
+
+    /// 撤销上一次作答，恢复计数、剩余练习次数和最近记录，并记入撤销日志
+    pub fn undo_last_answer(&mut self) -> bool {
+        let Some(snapshot) = self.undo.take() else {
+            self.last_error = Some("没有可撤销的作答(每个字根只能撤销一次)".to_string());
+            return false;
+        };
+        let text = snapshot.record.radical.clone();
+        self.current_radical = snapshot.current_radical;
+        match snapshot.remaining {
+            Some(count) => self.remaining_practice.insert(text.clone(), count),
+            None => self.remaining_practice.remove(&text),
+        };
+        self.correct_count = snapshot.correct_count;
+        self.wrong_count = snapshot.wrong_count;
+        self.total_practice = snapshot.total_practice;
+        self.recent_radicals = snapshot.recent_radicals;
+        self.last_big_code = snapshot.last_big_code;
+        self.recent_results = snapshot.recent_results;
+        if snapshot.first_seen {
+            self.seen.remove(&text);
+        }
+        self.choices = snapshot.choices;
+        self.hint_level = snapshot.hint_level;
+        self.combo = snapshot.combo;
+        self.shown_at = Some(Instant::now());
+        self.undo_used = true;
+        self.last_error = Some(format!("【撤销】已撤销对“{}”的作答，请重新输入", text));
+        self.undo_log.push(snapshot.record);
+        true
+    }
+
+    /// 当前字根在该练习模式下逐级给出的提示，最后一级给出完整答案
+    fn hint_steps(&self, config: &GameConfig) -> Vec<HintStep> {
+        let Some(radical) = self.current_radical() else {
+            return Vec::new();
+        };
+        match config.practice_mode {
+            PracticeMode::Choice | PracticeMode::ReverseChoice => Vec::new(),
+            // 答案只有大码，或者没有小码时大码就是完整编码
+            PracticeMode::BigCode => vec![HintStep::BigKey],
+            _ if radical.small_code.is_empty() => vec![HintStep::BigKey],
+            // 仅小码模式已经给出大码，提示直接给出小码
+            PracticeMode::SmallCode => vec![HintStep::SmallCode],
+            PracticeMode::DualCode => vec![HintStep::BigKey, HintStep::FullCode],
+        }
+    }
+
+    /// 用过提示后答对时额外增加的练习次数，按已提示的部分占答案的比例计入惩罚
+    fn hint_penalty(&self, penalty: usize, config: &GameConfig) -> usize {
+        let steps = self.hint_steps(config).len();
+        if steps == 0 {
+            return 0;
+        }
+        (penalty * self.hint_level.min(steps)).div_ceil(steps)
+    }
+
+    /// 逐级给出提示：正常练习先高亮大码键位再给出完整编码，只练大码或小码时一次给出答案
+    pub fn request_hint(&mut self, config: &GameConfig) {
+        if config.practice_mode.is_choice() {
+            self.last_error = Some("四选一模式没有提示".to_string());
+            return;
+        }
+        let steps = self.hint_steps(config);
+        let Some(radical) = self.current_radical() else {
+            return;
+        };
+        let level = (self.hint_level + 1).min(steps.len());
+        let hint = match steps[level - 1] {
+            HintStep::BigKey => {
+                // 只有正常模式会显示键盘，其他模式直接给出大码
+                let key = if config.mode == GameMode::Normal {
+                    "大码键位已在键盘上高亮".to_string()
+                } else {
+                    format!("大码是 {}", radical.big_code.to_uppercase())
+                };
+                match self.notes.describe(radical) {
+                    Some(note) => format!("{}\n{}", key, note),
+                    None => key,
+                }
+            }
+            HintStep::SmallCode => match self.notes.describe(radical) {
+                Some(note) => format!("小码是 {}\n{}", radical.small_code.to_lowercase(), note),
+                None => format!("小码是 {}", radical.small_code.to_lowercase()),
+            },
+            HintStep::FullCode => format!("编码是 {}", radical.display_code()),
+        };
+        self.hint_level = level;
+        self.last_error = Some(format!("【提示{}/{}】{}", level, steps.len(), hint));
+    }
+
+    /// 提示中需要高亮的大码键位(大写)
+    pub fn hint_key(&self) -> Option<String> {
+        if self.hint_level == 0 {
+            return None;
+        }
+        self.current_radical().map(|r| r.big_code.to_uppercase())
+    }
+
+    /// 当前字根在打字模式下期望输入的编码(小写)，四选一模式返回None
+    pub fn expected_answer(&self, config: &GameConfig) -> Option<String> {
+        let radical = self.current_radical()?;
+        let answer = match config.practice_mode {
+            PracticeMode::BigCode => &radical.big_code,
+            PracticeMode::SmallCode if !radical.small_code.is_empty() => &radical.small_code,
+            PracticeMode::SmallCode | PracticeMode::DualCode => &radical.code,
+            PracticeMode::Choice | PracticeMode::ReverseChoice => return None,
+        };
+        Some(answer.to_lowercase())
+    }
+
+    /// 逐键反馈模式下输入是否已达到期望的编码长度，达到时自动提交
+    pub fn input_complete(&self, input: &str, config: &GameConfig) -> bool {
+        config.instant_feedback
+            && self
+                .expected_answer(config)
+                .is_some_and(|e| input.chars().count() >= e.chars().count())
+    }
+
+    /// 四选一模式下根据输入的序号(1-4)取得所选字根
+    fn chosen_radical(&self, input: &str) -> Option<&Radical> {
+        let n: usize = input.trim().parse().ok()?;
+        let idx = *self.choices.get(n.checked_sub(1)?)?;
+        self.radicals.get(idx)
+    }
+
+    /// 为当前字根生成四选一的候选项
+    ///
+    /// 干扰项优先取同一大码键上的字根，其次取编码相近(小码相同或只差一个字母)的字根，
+    /// 不足时再随机补齐。候选项之间编码互不相同，避免出现多个正确答案
+    pub fn refresh_choices(&mut self, config: &GameConfig) {
+        self.choices.clear();
+        if !config.practice_mode.is_choice() {
+            return;
+        }
+        let Some(current) = self.current_radical() else {
+            return;
+        };
+        let current_code = current.code.to_lowercase();
+        let current_big = current.big_code.to_lowercase();
+        let current_small = current.small_code.to_lowercase();
+
+        let mut rng = rng();
+        let mut same_key = Vec::new();
+        let mut similar = Vec::new();
+        let mut others = Vec::new();
+        for (i, r) in self.radicals.iter().enumerate() {
+            let code = r.code.to_lowercase();
+            if code == current_code {
+                continue;
+            }
+            if r.big_code.to_lowercase() == current_big {
+                same_key.push(i);
+            } else if r.small_code.to_lowercase() == current_small
+                || code_distance(&code, &current_code) <= 1
+            {
+                similar.push(i);
+            } else {
+                others.push(i);
+            }
+        }
+        same_key.shuffle(&mut rng);
+        similar.shuffle(&mut rng);
+        others.shuffle(&mut rng);
+
+        // 最多两个同键干扰项，让另一个来自相近编码，其余按优先级补齐
+        let pools = same_key
+            .iter()
+            .take(2)
+            .chain(similar.iter())
+            .chain(same_key.iter().skip(2))
+            .chain(others.iter());
+        let mut used_codes = vec![current_code];
+        let mut choices = vec![self.current_radical];
+        for &i in pools {
+            if choices.len() == 4 {
+                break;
+            }
+            let code = self.radicals[i].code.to_lowercase();
+            if !used_codes.contains(&code) {
+                used_codes.push(code);
+                choices.push(i);
+            }
+        }
+        choices.shuffle(&mut rng);
+        self.choices = choices;
+    }
+
     /// 移动到下一个字根
     pub fn next_radical(&mut self, config: &GameConfig) -> bool {
         // 防御性检查：确保有字根可练习
@@ -794,6 +1254,11 @@ impl GameState {
                 indices.shuffle(&mut rng);
                 indices
             }
+            PracticeOrder::Weighted => {
+                // 按频率加权随机，高频字根更常出现
+                let indices = (0..self.radicals.len()).collect();
+                weighted_shuffle(&self.radicals, indices, config.weight_power)
+            }
         };
 
         // 生成随机间隔(3-6)
@@ -805,7 +1270,7 @@ impl GameState {
                 let should_retain = self
                     .remaining_practice
                     .get(&radical.text)
-                    .map_or(false, |&c| c > 0)
+                    .is_some_and(|&c| c > 0)
                     && !self
                         .recent_radicals
                         .iter()
@@ -818,6 +1283,18 @@ impl GameState {
             }
         });
 
+        // 自适应模式下正确率不达标时，暂不引入新字根，集中巩固已练过的
+        if !self.allows_new_radicals(config) {
+            let seen: Vec<usize> = candidates
+                .iter()
+                .copied()
+                .filter(|&i| self.seen.contains(&self.radicals[i].text))
+                .collect();
+            if !seen.is_empty() {
+                candidates = seen;
+            }
+        }
+
         // 如果没有符合条件的字根，则放宽条件
         if candidates.is_empty() {
             candidates = (0..self.radicals.len())
@@ -825,7 +1302,7 @@ impl GameState {
                     if let Some(radical) = self.radicals.get(i) {
                         self.remaining_practice
                             .get(&radical.text)
-                            .map_or(false, |&c| c > 0)
+                            .is_some_and(|&c| c > 0)
                     } else {
                         false
                     }
@@ -835,20 +1312,128 @@ impl GameState {
 
         // 选择下一个字根
         if let Some(&next_idx) = candidates.first() {
-            // 更新最近练习的字根列表
-            if let Some(radical) = self.radicals.get(next_idx) {
-                self.recent_radicals.insert(0, radical.text.clone());
-                if self.recent_radicals.len() > 6 {
-                    self.recent_radicals.pop();
-                }
-            }
-            self.current_radical = next_idx;
+            self.show_radical(next_idx, config);
             true
         } else {
             false
         }
     }
 
+    /// 切换到指定的字根并开始计时
+    pub fn show_radical(&mut self, idx: usize, config: &GameConfig) {
+        // 更新最近练习的字根列表
+        if let Some(radical) = self.radicals.get(idx) {
+            self.recent_radicals.insert(0, radical.text.clone());
+            if self.recent_radicals.len() > 6 {
+                self.recent_radicals.pop();
+            }
+        }
+        self.current_radical = idx;
+        self.shown_at = Some(Instant::now());
+        self.undo_used = false;
+        self.hint_level = 0;
+        self.refresh_choices(config);
+    }
+
+    /// 按练习顺序抽取count个不重复的字根，多名玩家使用同一组题目
+    pub fn draw_sequence(&self, count: usize, config: &GameConfig) -> Vec<usize> {
+        let mut scratch = self.clone();
+        let mut sequence = Vec::new();
+        while sequence.len() < count && scratch.next_radical(config) {
+            let idx = scratch.current_radical;
+            if let Some(radical) = scratch.radicals.get(idx) {
+                scratch.remaining_practice.insert(radical.text.clone(), 0);
+            }
+            sequence.push(idx);
+        }
+        sequence
+    }
+
+    /// 题面的标题和内容
+    pub fn prompt(&self, config: &GameConfig) -> Option<(&'static str, String)> {
+        let radical = self.current_radical()?;
+        Some(match config.practice_mode {
+            // 看编码选字根时题面显示编码
+            PracticeMode::ReverseChoice => ("当前编码", radical.display_code()),
+            // 仅小码模式把大码作为已知条件给出
+            PracticeMode::SmallCode => (
+                "当前字根",
+                format!(
+                    "{}    大码: {}",
+                    radical.display_text(),
+                    radical.big_code.to_uppercase()
+                ),
+            ),
+            _ => ("当前字根", radical.display_text()),
+        })
+    }
+
+    /// 四选一模式的选项文字，如"1. Af"
+    pub fn choice_labels(&self, config: &GameConfig) -> Vec<String> {
+        self.choices
+            .iter()
+            .enumerate()
+            .filter_map(|(n, &i)| {
+                let r = self.radicals.get(i)?;
+                let label = match config.practice_mode {
+                    PracticeMode::ReverseChoice => r.display_text(),
+                    _ => r.display_code(),
+                };
+                Some(format!("{}. {}", n + 1, label))
+            })
+            .collect()
+    }
+
+    /// 最近作答的正确率(0.0-1.0)，没有记录时返回None
+    pub fn rolling_accuracy(&self) -> Option<f64> {
+        if self.recent_results.is_empty() {
+            return None;
+        }
+        let correct = self.recent_results.iter().filter(|r| r.correct).count();
+        Some(correct as f64 / self.recent_results.len() as f64)
+    }
+
+    /// 最近作答的中位反应时间(毫秒)
+    pub fn rolling_median_latency(&self) -> Option<u64> {
+        let mut latencies: Vec<u64> = self
+            .recent_results
+            .iter()
+            .filter_map(|r| r.latency_ms)
+            .collect();
+        if latencies.is_empty() {
+            return None;
+        }
+        latencies.sort_unstable();
+        Some(latencies[latencies.len() / 2])
+    }
+
+    /// 当前实际使用的错误惩罚次数
+    ///
+    /// 自适应模式下按近期正确率与目标的差距调整：达标时只罚1次，
+    /// 每低5个百分点多罚1次，反应过慢再加1次，最多10次
+    pub fn effective_penalty(&self, config: &GameConfig) -> usize {
+        if config.adaptive_target == 0 || self.recent_results.len() < ADAPTIVE_WARMUP {
+            return config.penalty;
+        }
+        let accuracy = self.rolling_accuracy().unwrap_or(1.0) * 100.0;
+        let gap = (config.adaptive_target as f64 - accuracy).max(0.0);
+        let mut penalty = 1 + (gap / 5.0).ceil() as usize;
+        if self.rolling_median_latency().is_some_and(|l| l > SLOW_LATENCY_MS) {
+            penalty += 1;
+        }
+        penalty.min(10)
+    }
+
+    /// 是否允许引入尚未练过的新字根
+    fn allows_new_radicals(&self, config: &GameConfig) -> bool {
+        if config.adaptive_target == 0 || self.recent_results.len() < ADAPTIVE_WARMUP {
+            return true;
+        }
+        let accuracy = self.rolling_accuracy().unwrap_or(1.0) * 100.0;
+        accuracy >= config.adaptive_target as f64
+            && self.rolling_median_latency().is_none_or(|l| l <= SLOW_LATENCY_MS)
+    }
+
     /// 检查游戏是否结束
     pub fn is_game_over(&self) -> bool {
         self.remaining_practice.values().all(|&c| c == 0)
@@ -863,3 +1448,462 @@ impl GameState {
         (completed, total)
     }
 }
+
+/// 按频率加权随机排列索引，权重为(频率+1)的power次方
+///
+/// 采用Efraimidis-Spirakis加权抽样：每项取键值ln(u)/w后降序排列，
+/// 排在前面的项被抽中的概率与其权重成正比
+fn weighted_shuffle(radicals: &[Radical], indices: Vec<usize>, power: f64) -> Vec<usize> {
+    let mut rng = rng();
+    let mut keyed: Vec<(f64, usize)> = indices
+        .into_iter()
+        .map(|i| {
+            let weight = (radicals[i].frequency as f64 + 1.0).powf(power);
+            let u: f64 = rng.random_range(f64::EPSILON..1.0);
+            (u.ln() / weight, i)
+        })
+        .collect();
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+    keyed.into_iter().map(|(_, i)| i).collect()
+}
+
+/// 两个编码之间不同字母的个数(长度不同的部分也计入)
+fn code_distance(a: &str, b: &str) -> usize {
+    let diff = a.chars().zip(b.chars()).filter(|(x, y)| x != y).count();
+    diff + a.chars().count().abs_diff(b.chars().count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn radical(text: &str, examples: &str) -> Radical {
+        Radical {
+            code: "sv".to_string(),
+            text: text.to_string(),
+            frequency: 0,
+            big_code: "s".to_string(),
+            small_code: "v".to_string(),
+            examples: examples.to_string(),
+            note: String::new(),
+        }
+    }
+
+    /// 编码为code(首字母为大码)的字根
+    fn coded(text: &str, code: &str) -> Radical {
+        Radical {
+            code: code.to_string(),
+            big_code: code[..1].to_string(),
+            small_code: code[1..].to_string(),
+            ..radical(text, "")
+        }
+    }
+
+    fn config(practice_mode: PracticeMode) -> GameConfig {
+        GameConfig {
+            radical_file: String::new(),
+            frequency_file: String::new(),
+            penalty: 4,
+            min_practice_count: 2,
+            practice_mode,
+            order: PracticeOrder::Alphabetical,
+            mode: GameMode::Normal,
+            weight_power: 1.0,
+            adaptive_target: 0,
+            instant_feedback: false,
+            time_limit: 0,
+            cancelled: false,
+        }
+    }
+
+    /// 最近作答中有correct次答对、其余答错，反应时间都是latency_ms
+    fn with_results(correct: usize, total: usize, latency_ms: u64) -> GameState {
+        let mut state = GameState::new(vec![radical("口", "")], &config(PracticeMode::DualCode));
+        state.recent_results = (0..total)
+            .map(|i| AnswerRecord {
+                correct: i < correct,
+                latency_ms: Some(latency_ms),
+            })
+            .collect();
+        state
+    }
+
+    #[test]
+    fn brace_radical_shows_host_character() {
+        assert_eq!(
+            radical("{曹上}", "").display_text(),
+            "{曹上}(“曹”的上部，例字: 曹)"
+        );
+        assert_eq!(
+            radical("{南心}", "献").display_text(),
+            "{南心}(“南”的内部，例字: 南 献)"
+        );
+        // 例字数据里已有部件名中的汉字时不重复
+        assert_eq!(
+            radical("{奉下}", "捧奉棒俸").display_text(),
+            "{奉下}(“奉”的下部，例字: 捧 奉 棒)"
+        );
+    }
+
+    #[test]
+    fn brace_radical_uses_examples_column() {
+        assert_eq!(
+            radical("{横日}", "曾增").display_text(),
+            "{横日}(部件“横日”，例字: 曾 增)"
+        );
+        assert_eq!(radical("{横日}", "").display_text(), "{横日}(部件“横日”)");
+    }
+
+    #[test]
+    fn plain_radical_is_shown_as_is() {
+        assert_eq!(radical("口", "和").display_text(), "口");
+    }
+
+    #[test]
+    fn private_use_radical_with_and_without_examples() {
+        assert_eq!(
+            radical("\u{E000}", "曾增").display_text(),
+            "U+E000(私用区字符，例字: 曾 增)"
+        );
+        assert_eq!(
+            radical("\u{E000}", "").display_text(),
+            "U+E000(私用区字符“\u{E000}”，需要宇浩字体显示)"
+        );
+        assert_eq!(
+            radical("\u{F0001}", "").display_text(),
+            "U+F0001(私用区字符“\u{F0001}”，需要宇浩字体显示)"
+        );
+    }
+
+    #[test]
+    fn weighted_shuffle_puts_frequent_radicals_first() {
+        let radicals = vec![
+            radical("丶", ""),
+            Radical {
+                frequency: 1000,
+                ..radical("口", "")
+            },
+            Radical {
+                frequency: 10,
+                ..radical("日", "")
+            },
+        ];
+        let mut first = [0; 3];
+        for _ in 0..2000 {
+            let order = weighted_shuffle(&radicals, vec![0, 1, 2], 1.0);
+            let mut sorted = order.clone();
+            sorted.sort_unstable();
+            assert_eq!(sorted, [0, 1, 2]);
+            first[order[0]] += 1;
+        }
+        // 权重1001比11比1，排第一的期望次数约为1976、22、2，以下判断的误判概率都可以忽略
+        assert!(first[1] >= 1900, "{:?}", first);
+        assert!(first[2] > first[0], "{:?}", first);
+    }
+
+    #[test]
+    fn adaptive_penalty_follows_accuracy_gap() {
+        let mut config = config(PracticeMode::DualCode);
+        // 关闭时或作答次数不足时使用设置的惩罚
+        assert_eq!(with_results(0, 20, 1000).effective_penalty(&config), 4);
+        config.adaptive_target = 80;
+        assert_eq!(
+            with_results(0, ADAPTIVE_WARMUP - 1, 1000).effective_penalty(&config),
+            4
+        );
+
+        assert_eq!(with_results(16, 20, 1000).effective_penalty(&config), 1);
+        assert_eq!(with_results(20, 20, 1000).effective_penalty(&config), 1);
+        // 每低5个百分点多罚1次
+        assert_eq!(with_results(15, 20, 1000).effective_penalty(&config), 2);
+        assert_eq!(with_results(14, 20, 1000).effective_penalty(&config), 3);
+        // 反应过慢再加1次
+        assert_eq!(
+            with_results(16, 20, SLOW_LATENCY_MS).effective_penalty(&config),
+            1
+        );
+        assert_eq!(
+            with_results(16, 20, SLOW_LATENCY_MS + 1).effective_penalty(&config),
+            2
+        );
+        // 最多10次
+        assert_eq!(
+            with_results(0, 20, SLOW_LATENCY_MS + 1).effective_penalty(&config),
+            10
+        );
+    }
+
+    #[test]
+    fn new_radicals_wait_for_target_accuracy() {
+        let mut config = config(PracticeMode::DualCode);
+        assert!(with_results(0, 20, 1000).allows_new_radicals(&config));
+        config.adaptive_target = 80;
+        assert!(with_results(0, ADAPTIVE_WARMUP - 1, 1000).allows_new_radicals(&config));
+
+        assert!(with_results(16, 20, 1000).allows_new_radicals(&config));
+        assert!(!with_results(15, 20, 1000).allows_new_radicals(&config));
+        assert!(with_results(16, 20, SLOW_LATENCY_MS).allows_new_radicals(&config));
+        assert!(!with_results(16, 20, SLOW_LATENCY_MS + 1).allows_new_radicals(&config));
+    }
+
+    #[test]
+    fn choices_are_four_distinct_codes_including_answer() {
+        let radicals = vec![
+            coded("口", "ko"),
+            coded("日", "od"),
+            coded("月", "ye"),
+            coded("木", "mu"),
+            coded("目", "ou"),
+            coded("田", "oi"),
+            coded("土", "tu"),
+            coded("士", "tu"),
+        ];
+        let config = config(PracticeMode::Choice);
+        let mut state = GameState::new(radicals, &config);
+        for current in 0..state.radicals.len() {
+            state.current_radical = current;
+            state.refresh_choices(&config);
+            assert_eq!(state.choices.len(), 4);
+            assert!(state.choices.contains(&current));
+            let mut codes: Vec<&str> = state
+                .choices
+                .iter()
+                .map(|&i| state.radicals[i].code.as_str())
+                .collect();
+            codes.sort_unstable();
+            codes.dedup();
+            assert_eq!(codes.len(), 4, "候选编码重复: {:?}", codes);
+        }
+    }
+
+    #[test]
+    fn choices_with_fewer_than_four_codes() {
+        // 同码字根不能作为干扰项，否则看编码选字根时会有两个正确答案
+        let radicals = vec![coded("土", "tu"), coded("士", "tu"), coded("口", "ko")];
+        let config = config(PracticeMode::ReverseChoice);
+        let mut state = GameState::new(radicals, &config);
+        state.refresh_choices(&config);
+        assert_eq!(state.choices.len(), 2);
+        assert!(state.choices.contains(&state.current_radical));
+        assert_eq!(state.choice_labels(&config).len(), 2);
+
+        let config = GameConfig {
+            practice_mode: PracticeMode::Choice,
+            ..config
+        };
+        let mut state = GameState::new(vec![coded("口", "ko")], &config);
+        state.refresh_choices(&config);
+        assert_eq!(state.choices, [0]);
+        // 超出候选数的数字键不算作答
+        assert!(!state.check_input("2", &config));
+        assert_eq!(state.total_practice, 0);
+        assert!(state.check_input("1", &config));
+    }
+
+    #[test]
+    fn choices_are_empty_outside_choice_modes() {
+        let config = config(PracticeMode::DualCode);
+        let mut state = GameState::new(vec![coded("口", "ko"), coded("日", "od")], &config);
+        state.refresh_choices(&config);
+        assert!(state.choices.is_empty());
+    }
+
+    #[test]
+    fn feedback_names_the_wrong_code_part() {
+        let cases = [
+            (PracticeMode::DualCode, "ko", None),
+            (PracticeMode::DualCode, "KO", None),
+            (PracticeMode::DualCode, "ka", Some("小码错误")),
+            (PracticeMode::DualCode, "k", Some("小码错误")),
+            (PracticeMode::DualCode, "jo", Some("大码错误")),
+            (PracticeMode::DualCode, "ja", Some("大小码均错误")),
+            (PracticeMode::DualCode, "j", Some("大小码均错误")),
+            (PracticeMode::SmallCode, "o", None),
+            (PracticeMode::SmallCode, "ko", None),
+            (PracticeMode::SmallCode, "a", Some("小码错误")),
+            (PracticeMode::SmallCode, "ka", Some("小码错误")),
+            (PracticeMode::BigCode, "k", None),
+            (PracticeMode::BigCode, "j", Some("")),
+        ];
+        for (mode, input, wrong_part) in cases {
+            let config = config(mode);
+            let state = GameState::new(vec![coded("口", "ko")], &config);
+            let (correct, message) = state.check_input_core(input, &config);
+            let message = message.unwrap();
+            assert_eq!(correct, wrong_part.is_none(), "{:?} {}", mode, input);
+            match wrong_part {
+                None => assert!(message.starts_with("【正确】“口”"), "{}", message),
+                Some("") => assert!(message.starts_with("【错误】“口”"), "{}", message),
+                Some(part) => assert!(
+                    message.starts_with(&format!("【错误】{}，“口”", part)),
+                    "{:?} {}: {}",
+                    mode,
+                    input,
+                    message
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn expected_answer_per_mode() {
+        let radicals = vec![coded("口", "ko"), coded("一", "a")];
+        let cases = [
+            (PracticeMode::BigCode, Some("k"), Some("a")),
+            (PracticeMode::SmallCode, Some("o"), Some("a")),
+            (PracticeMode::DualCode, Some("ko"), Some("a")),
+            (PracticeMode::Choice, None, None),
+            (PracticeMode::ReverseChoice, None, None),
+        ];
+        for (mode, first, second) in cases {
+            let config = config(mode);
+            let mut state = GameState::new(radicals.clone(), &config);
+            let index = |text| state.radicals.iter().position(|r| r.text == text).unwrap();
+            state.current_radical = index("口");
+            assert_eq!(
+                state.expected_answer(&config).as_deref(),
+                first,
+                "{:?}",
+                mode
+            );
+            // 没有小码的字根在仅小码模式下要输入完整编码
+            state.current_radical = index("一");
+            assert_eq!(
+                state.expected_answer(&config).as_deref(),
+                second,
+                "{:?}",
+                mode
+            );
+        }
+    }
+
+    #[test]
+    fn auto_submit_at_code_length() {
+        let cases = [
+            (PracticeMode::BigCode, 1),
+            (PracticeMode::SmallCode, 1),
+            (PracticeMode::DualCode, 2),
+        ];
+        for (mode, length) in cases {
+            let mut config = config(mode);
+            config.instant_feedback = true;
+            let state = GameState::new(vec![coded("口", "ko")], &config);
+            let input = "kox";
+            for n in 0..=input.len() {
+                assert_eq!(
+                    state.input_complete(&input[..n], &config),
+                    n >= length,
+                    "{:?} {}",
+                    mode,
+                    n
+                );
+            }
+            // 关闭逐键反馈时总是等待Enter
+            config.instant_feedback = false;
+            assert!(!state.input_complete("ko", &config));
+        }
+
+        // 四选一模式按数字键直接提交，不走自动提交
+        let mut config = config(PracticeMode::Choice);
+        config.instant_feedback = true;
+        let state = GameState::new(vec![coded("口", "ko")], &config);
+        assert!(!state.input_complete("1", &config));
+    }
+
+    #[test]
+    fn undo_restores_state_once_per_radical() {
+        let config = config(PracticeMode::DualCode);
+        let mut state = GameState::new(vec![coded("口", "ko"), coded("木", "mu")], &config);
+        let first = state.current_radical;
+        let text = state.radicals[first].text.clone();
+
+        assert!(!state.check_input("zz", &config));
+        assert_eq!(
+            (state.correct_count, state.wrong_count, state.total_practice),
+            (0, 1, 1)
+        );
+        assert_eq!(state.remaining_practice[&text], 2 + 4);
+        state.next_radical(&config);
+        assert!(state.can_undo());
+
+        assert!(state.undo_last_answer());
+        assert_eq!(state.current_radical, first);
+        assert_eq!(
+            (state.correct_count, state.wrong_count, state.total_practice),
+            (0, 0, 0)
+        );
+        assert_eq!(state.remaining_practice[&text], 2);
+        assert!(state.recent_results.is_empty());
+        assert!(!state.seen.contains(&text));
+        assert_eq!(state.undo_log.len(), 1);
+        assert!(!state.undo_log[0].correct);
+
+        // 同一个字根撤销过一次后，重新作答不能再撤销
+        assert!(!state.undo_last_answer());
+        let answer = state.radicals[first].code.clone();
+        assert!(state.check_input(&answer, &config));
+        assert!(!state.can_undo());
+        assert_eq!(state.remaining_practice[&text], 1);
+    }
+
+    /// 按Tab taps次后答对，返回最后一级提示和答对后的剩余练习次数
+    fn answer_after_hints(mode: PracticeMode, code: &str, taps: usize) -> (String, usize) {
+        let config = config(mode);
+        let mut state = GameState::new(vec![coded("口", code)], &config);
+        for _ in 0..taps {
+            state.request_hint(&config);
+        }
+        let hint = state.last_error.clone().unwrap_or_default();
+        let answer = state.expected_answer(&config).unwrap();
+        assert!(state.check_input(&answer, &config));
+        (hint, state.remaining_practice["口"])
+    }
+
+    #[test]
+    fn hint_ladder_per_mode() {
+        // 正常练习：第一级高亮大码键位，按一半计入惩罚；第二级给出完整编码
+        let (hint, remaining) = answer_after_hints(PracticeMode::DualCode, "ko", 1);
+        assert!(
+            hint.starts_with("【提示1/2】大码键位已在键盘上高亮"),
+            "{}",
+            hint
+        );
+        assert_eq!(remaining, 2 - 1 + 2);
+        let (hint, remaining) = answer_after_hints(PracticeMode::DualCode, "ko", 2);
+        assert!(hint.starts_with("【提示2/2】编码是"), "{}", hint);
+        assert_eq!(remaining, 2 - 1 + 4);
+        // 已经到最后一级后不再升级
+        let (hint, remaining) = answer_after_hints(PracticeMode::DualCode, "ko", 3);
+        assert!(hint.starts_with("【提示2/2】"), "{}", hint);
+        assert_eq!(remaining, 2 - 1 + 4);
+
+        // 只练大码或小码时一级提示就是答案，按完整答错计入惩罚
+        let (hint, remaining) = answer_after_hints(PracticeMode::BigCode, "ko", 1);
+        assert!(
+            hint.starts_with("【提示1/1】大码键位已在键盘上高亮"),
+            "{}",
+            hint
+        );
+        assert_eq!(remaining, 2 - 1 + 4);
+        let (hint, remaining) = answer_after_hints(PracticeMode::SmallCode, "ko", 1);
+        assert_eq!(hint, "【提示1/1】小码是 o");
+        assert_eq!(remaining, 2 - 1 + 4);
+
+        // 没有小码的字根大码就是完整编码
+        let (hint, remaining) = answer_after_hints(PracticeMode::DualCode, "k", 1);
+        assert!(hint.starts_with("【提示1/1】"), "{}", hint);
+        assert_eq!(remaining, 2 - 1 + 4);
+
+        // 不用提示时正常减少练习次数
+        assert_eq!(answer_after_hints(PracticeMode::DualCode, "ko", 0).1, 1);
+    }
+
+    #[test]
+    fn choice_modes_have_no_hints() {
+        let config = config(PracticeMode::Choice);
+        let mut state = GameState::new(vec![coded("口", "ko")], &config);
+        state.request_hint(&config);
+        assert_eq!(state.last_error.as_deref(), Some("四选一模式没有提示"));
+        assert_eq!(state.hint_key(), None);
+    }
+}
@@ -0,0 +1,576 @@
+use crate::game::{GameConfig, GameState, PracticeMode, Radical};
+use crate::settings::{Field, SettingsForm};
+use crate::show_confirm_dialog;
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
+    Terminal,
+};
+use std::cmp::Reverse;
+use std::io;
+
+/// 分屏抢答时每名玩家使用的按键，依次对应四个选项
+const KEY_GROUPS: [&str; 4] = ["1234", "7890", "qwer", "uiop"];
+
+/// 轮流对战的设置
+struct Settings {
+    players: usize,   // 玩家人数(2-4)
+    questions: usize, // 每人题数
+    split: bool,      // 分屏抢答(四选一，各用一组按键同时作答)
+}
+
+/// 一名玩家的比赛状态
+struct Player {
+    name: String,
+    state: GameState, // 每名玩家独立的练习状态，题目相同
+    total_ms: u64,    // 累计作答用时(毫秒)
+}
+
+impl Player {
+    /// 排名先比答对数，再比答错数，最后比用时
+    fn rank_key(&self) -> (Reverse<usize>, usize, u64) {
+        (
+            Reverse(self.state.correct_count),
+            self.state.wrong_count,
+            self.total_ms,
+        )
+    }
+}
+
+/// 轮流对战：2-4名玩家在同一台电脑上依次回答同一组字根，或者分屏同时抢答，最后显示成绩排名
+pub fn run(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
+    let mut config = GameConfig::show_settings_menu(terminal)?;
+    if config.cancelled {
+        return Ok(());
+    }
+    let mut settings = Settings {
+        players: 2,
+        questions: 20,
+        split: false,
+    };
+    let fields = vec![
+        Field::choice(
+            "比赛方式",
+            "分屏抢答使用四选一模式，玩家1到4依次用1-4、7-0、Q-R、U-P键作答",
+            vec![(false, "轮流作答"), (true, "分屏抢答")],
+            |s: &Settings| s.split,
+            |s: &mut Settings, v| s.split = v,
+        ),
+        Field::choice(
+            "玩家人数",
+            "所有玩家作答同一组字根",
+            (2..=4).map(|n| (n, format!("{}人", n))).collect(),
+            |s: &Settings| s.players,
+            |s: &mut Settings, v| s.players = v,
+        ),
+        Field::choice(
+            "每人题数",
+            "每题只能作答一次，答错直接进入下一题",
+            [10, 20, 30, 50]
+                .into_iter()
+                .map(|n| (n, format!("{}题", n)))
+                .collect(),
+            |s: &Settings| s.questions,
+            |s: &mut Settings, v| s.questions = v,
+        ),
+    ];
+    if !SettingsForm::new("轮流对战", fields).run(terminal, &mut settings)? {
+        return Ok(());
+    }
+
+    // 分屏时只能按选项键作答，打字模式换成看字根选编码
+    if settings.split && !config.practice_mode.is_choice() {
+        config.practice_mode = PracticeMode::Choice;
+    }
+    let radicals = Radical::load_from_files(&config.frequency_file, &config.radical_file)?;
+    let template = GameState::new(radicals, &config);
+    let sequence = template.draw_sequence(settings.questions, &config);
+    let mut players: Vec<Player> = (1..=settings.players)
+        .map(|n| Player {
+            name: format!("玩家{}", n),
+            state: template.clone(),
+            total_ms: 0,
+        })
+        .collect();
+
+    if settings.split {
+        if !play_split(terminal, &config, &mut players, &sequence)? {
+            return Ok(());
+        }
+        return show_scoreboard(terminal, &players);
+    }
+    for player in &mut players {
+        if !wait_ready(terminal, &player.name, sequence.len())? {
+            return Ok(());
+        }
+        if !play_turn(terminal, &config, player, &sequence)? {
+            return Ok(());
+        }
+    }
+    show_scoreboard(terminal, &players)
+}
+
+/// 提示下一名玩家就位，ESC放弃比赛时返回false
+fn wait_ready(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    name: &str,
+    questions: usize,
+) -> Result<bool> {
+    terminal.draw(|f| {
+        let text = vec![
+            Line::from(format!("轮到{}，共{}题", name, questions)),
+            Line::from(""),
+            Line::from("按任意键开始，ESC放弃比赛"),
+        ];
+        let paragraph = Paragraph::new(text)
+            .block(Block::default().title("轮流对战").borders(Borders::ALL))
+            .alignment(Alignment::Center);
+        f.render_widget(paragraph, f.area());
+    })?;
+    loop {
+        if let Event::Key(key) = event::read()? {
+            #[cfg(windows)]
+            if key.kind != event::KeyEventKind::Press {
+                continue;
+            }
+            return Ok(key.code != KeyCode::Esc);
+        }
+    }
+}
+
+/// 一名玩家依次作答整组字根，放弃比赛时返回false
+fn play_turn(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    config: &GameConfig,
+    player: &mut Player,
+    sequence: &[usize],
+) -> Result<bool> {
+    let mut feedback: Option<String> = None;
+    for (n, &idx) in sequence.iter().enumerate() {
+        player.state.show_radical(idx, config);
+        let mut input = String::new();
+        loop {
+            let state = &player.state;
+            terminal.draw(|f| {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .margin(1)
+                    .constraints([
+                        Constraint::Length(3), // 玩家和进度
+                        Constraint::Length(3), // 当前字根
+                        Constraint::Length(3), // 输入框
+                        Constraint::Min(4),    // 上一题的反馈
+                        Constraint::Length(3), // 操作提示
+                    ])
+                    .split(f.area());
+
+                let status = format!(
+                    "{} | 第{}/{}题 | 正确: {} | 错误: {}",
+                    player.name,
+                    n + 1,
+                    sequence.len(),
+                    state.correct_count,
+                    state.wrong_count
+                );
+                let status = Paragraph::new(status)
+                    .block(Block::default().borders(Borders::ALL))
+                    .alignment(Alignment::Center);
+                f.render_widget(status, chunks[0]);
+
+                if let Some((title, prompt)) = state.prompt(config) {
+                    let prompt = Paragraph::new(prompt)
+                        .block(Block::default().title(title).borders(Borders::ALL))
+                        .alignment(Alignment::Center);
+                    f.render_widget(prompt, chunks[1]);
+                }
+
+                let (title, content) = if config.practice_mode.is_choice() {
+                    ("选择答案 (按1-4)", state.choice_labels(config).join("    "))
+                } else {
+                    ("输入编码 (Enter确认)", input.clone())
+                };
+                let input_box = Paragraph::new(content)
+                    .block(Block::default().title(title).borders(Borders::ALL))
+                    .alignment(Alignment::Center);
+                f.render_widget(input_box, chunks[2]);
+
+                if let Some(message) = &feedback {
+                    let color = if message.starts_with("【正确】") {
+                        Color::Green
+                    } else {
+                        Color::Red
+                    };
+                    let message = Paragraph::new(message.as_str())
+                        .style(Style::default().fg(color))
+                        .wrap(Wrap { trim: true })
+                        .block(Block::default().title("上一题").borders(Borders::ALL))
+                        .alignment(Alignment::Center);
+                    f.render_widget(message, chunks[3]);
+                }
+
+                let help = Paragraph::new("每题只能作答一次 | ESC: 放弃比赛")
+                    .block(Block::default().borders(Borders::ALL))
+                    .alignment(Alignment::Center);
+                f.render_widget(help, chunks[4]);
+            })?;
+
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            #[cfg(windows)]
+            if key.kind != event::KeyEventKind::Press {
+                continue;
+            }
+            let submit = match key.code {
+                KeyCode::Esc => {
+                    if show_confirm_dialog(terminal, "放弃本场比赛？")? {
+                        return Ok(false);
+                    }
+                    false
+                }
+                KeyCode::Char(c) if config.practice_mode.is_choice() => {
+                    input = c.to_string();
+                    true
+                }
+                KeyCode::Char(c) => {
+                    input.push(c);
+                    // 逐键反馈模式下输满编码长度自动提交
+                    player.state.input_complete(&input, config)
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                    false
+                }
+                KeyCode::Enter => true,
+                _ => false,
+            };
+            if !submit {
+                continue;
+            }
+
+            let answered = player.state.total_practice;
+            player.state.check_input(&input, config);
+            input.clear();
+            feedback = player.state.last_error.take();
+            // 无效输入(如空输入)不算作答，留在当前题目
+            if player.state.total_practice > answered {
+                let latency_ms = player.state.recent_results.back().and_then(|r| r.latency_ms);
+                player.total_ms += latency_ms.unwrap_or(0);
+                break;
+            }
+        }
+    }
+    Ok(true)
+}
+
+/// 分屏抢答的按键对应的(玩家, 选项序号)，选项序号从1开始
+fn split_key(c: char, players: usize) -> Option<(usize, usize)> {
+    let c = c.to_ascii_lowercase();
+    KEY_GROUPS
+        .iter()
+        .take(players)
+        .enumerate()
+        .find_map(|(player, keys)| keys.find(c).map(|n| (player, n + 1)))
+}
+
+/// 分屏抢答：所有玩家同时按各自的按键作答同一组字根，每题只能作答一次，放弃比赛时返回false
+fn play_split(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    config: &GameConfig,
+    players: &mut [Player],
+    sequence: &[usize],
+) -> Result<bool> {
+    // 每名玩家当前的题号和上一题的反馈
+    let mut progress: Vec<(usize, Option<String>)> = vec![(0, None); players.len()];
+    if let Some(&first) = sequence.first() {
+        for player in players.iter_mut() {
+            player.state.show_radical(first, config);
+        }
+    }
+    while progress.iter().any(|(n, _)| *n < sequence.len()) {
+        terminal.draw(|f| {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .margin(1)
+                .constraints([Constraint::Min(10), Constraint::Length(3)])
+                .split(f.area());
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(vec![
+                    Constraint::Ratio(1, players.len() as u32);
+                    players.len()
+                ])
+                .split(chunks[0]);
+
+            for (i, player) in players.iter().enumerate() {
+                let (n, feedback) = &progress[i];
+                let keys = KEY_GROUPS[i].to_uppercase();
+                let mut lines = vec![
+                    Line::from(format!(
+                        "正确: {} | 错误: {}",
+                        player.state.correct_count, player.state.wrong_count
+                    )),
+                    Line::from(""),
+                ];
+                if *n < sequence.len() {
+                    if let Some((title, prompt)) = player.state.prompt(config) {
+                        lines.push(Line::from(format!("{}: {}", title, prompt)));
+                        lines.push(Line::from(""));
+                    }
+                    let labels = player.state.choice_labels(config);
+                    for (key, label) in keys.chars().zip(labels) {
+                        let text = label.split_once(". ").map_or(label.as_str(), |(_, t)| t);
+                        lines.push(Line::from(format!("{}. {}", key, text)));
+                    }
+                } else {
+                    lines.push(Line::styled("已完成", Style::default().fg(Color::Yellow)));
+                }
+                if let Some(message) = feedback {
+                    let color = if message.starts_with("【正确】") {
+                        Color::Green
+                    } else {
+                        Color::Red
+                    };
+                    lines.push(Line::from(""));
+                    for line in message.lines() {
+                        lines.push(Line::styled(line.to_string(), Style::default().fg(color)));
+                    }
+                }
+                let title = format!(
+                    "{} ({}) 第{}/{}题",
+                    player.name,
+                    keys,
+                    (*n + 1).min(sequence.len()),
+                    sequence.len()
+                );
+                let panel = Paragraph::new(lines)
+                    .wrap(Wrap { trim: true })
+                    .block(Block::default().title(title).borders(Borders::ALL))
+                    .alignment(Alignment::Center);
+                f.render_widget(panel, columns[i]);
+            }
+
+            let help = Paragraph::new("每题只能作答一次，全部玩家答完后显示成绩 | ESC: 放弃比赛")
+                .block(Block::default().borders(Borders::ALL))
+                .alignment(Alignment::Center);
+            f.render_widget(help, chunks[1]);
+        })?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        #[cfg(windows)]
+        if key.kind != event::KeyEventKind::Press {
+            continue;
+        }
+        let (i, choice) = match key.code {
+            KeyCode::Esc => {
+                if show_confirm_dialog(terminal, "放弃本场比赛？")? {
+                    return Ok(false);
+                }
+                continue;
+            }
+            KeyCode::Char(c) => match split_key(c, players.len()) {
+                Some(pressed) => pressed,
+                None => continue,
+            },
+            _ => continue,
+        };
+        let (n, feedback) = &mut progress[i];
+        if *n >= sequence.len() {
+            continue;
+        }
+        let player = &mut players[i];
+        let answered = player.state.total_practice;
+        player.state.check_input(&choice.to_string(), config);
+        *feedback = player.state.last_error.take();
+        // 候选项不足四个时多余的按键不算作答
+        if player.state.total_practice == answered {
+            continue;
+        }
+        let latency_ms = player
+            .state
+            .recent_results
+            .back()
+            .and_then(|r| r.latency_ms);
+        player.total_ms += latency_ms.unwrap_or(0);
+        *n += 1;
+        if let Some(&idx) = sequence.get(*n) {
+            player.state.show_radical(idx, config);
+        }
+    }
+    Ok(true)
+}
+
+/// 按成绩排序的玩家和名次，成绩相同的玩家名次相同
+fn ranking(players: &[Player]) -> Vec<(usize, &Player)> {
+    let mut sorted: Vec<&Player> = players.iter().collect();
+    sorted.sort_by_key(|p| p.rank_key());
+    sorted
+        .iter()
+        .map(|p| {
+            let rank = sorted
+                .iter()
+                .filter(|other| other.rank_key() < p.rank_key())
+                .count()
+                + 1;
+            (rank, *p)
+        })
+        .collect()
+}
+
+/// 比赛结束的成绩排名，成绩相同的玩家名次相同
+fn show_scoreboard(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    players: &[Player],
+) -> Result<()> {
+    let ranking = ranking(players);
+    terminal.draw(|f| {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(1)
+            .constraints([Constraint::Min(5), Constraint::Length(3)])
+            .split(f.area());
+
+        let items: Vec<ListItem> = ranking
+            .iter()
+            .map(|&(rank, p)| {
+                let answers = p.state.correct_count + p.state.wrong_count;
+                let accuracy = if answers == 0 {
+                    0.0
+                } else {
+                    p.state.correct_count as f64 / answers as f64 * 100.0
+                };
+                let style = if rank == 1 {
+                    Style::default().fg(Color::Yellow)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(format!(
+                    "第{}名  {}  正确: {}  错误: {}  正确率: {:.0}%  用时: {:.1}秒",
+                    rank,
+                    p.name,
+                    p.state.correct_count,
+                    p.state.wrong_count,
+                    accuracy,
+                    p.total_ms as f64 / 1000.0
+                ))
+                .style(style)
+            })
+            .collect();
+        let list = List::new(items).block(Block::default().title("比赛结果").borders(Borders::ALL));
+        f.render_widget(list, chunks[0]);
+
+        let help = Paragraph::new("Enter/ESC: 返回")
+            .block(Block::default().borders(Borders::ALL))
+            .alignment(Alignment::Center);
+        f.render_widget(help, chunks[1]);
+    })?;
+    loop {
+        if let Event::Key(key) = event::read()? {
+            #[cfg(windows)]
+            if key.kind != event::KeyEventKind::Press {
+                continue;
+            }
+            if matches!(key.code, KeyCode::Enter | KeyCode::Esc) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{GameMode, PracticeOrder};
+
+    fn player(name: &str, correct: usize, wrong: usize, total_ms: u64) -> Player {
+        let radical = Radical {
+            code: "ko".to_string(),
+            text: "口".to_string(),
+            frequency: 0,
+            big_code: "k".to_string(),
+            small_code: "o".to_string(),
+            examples: String::new(),
+            note: String::new(),
+        };
+        let config = GameConfig {
+            radical_file: String::new(),
+            frequency_file: String::new(),
+            penalty: 4,
+            min_practice_count: 1,
+            practice_mode: PracticeMode::Choice,
+            order: PracticeOrder::Alphabetical,
+            mode: GameMode::Normal,
+            weight_power: 1.0,
+            adaptive_target: 0,
+            instant_feedback: false,
+            time_limit: 0,
+            cancelled: false,
+        };
+        let mut state = GameState::new(vec![radical], &config);
+        state.correct_count = correct;
+        state.wrong_count = wrong;
+        Player {
+            name: name.to_string(),
+            state,
+            total_ms,
+        }
+    }
+
+    fn ranks(players: &[Player]) -> Vec<(usize, &str)> {
+        ranking(players)
+            .into_iter()
+            .map(|(rank, p)| (rank, p.name.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn ranking_orders_by_correct_wrong_then_time() {
+        let players = [
+            player("慢", 18, 2, 40_000),
+            player("快", 18, 2, 30_000),
+            player("错少", 18, 1, 90_000),
+            player("对多", 19, 10, 99_000),
+        ];
+        assert_eq!(
+            ranks(&players),
+            [(1, "对多"), (2, "错少"), (3, "快"), (4, "慢")]
+        );
+    }
+
+    #[test]
+    fn tied_players_share_a_rank() {
+        let players = [
+            player("甲", 10, 0, 5_000),
+            player("乙", 12, 0, 9_000),
+            player("丙", 10, 0, 5_000),
+            player("丁", 9, 0, 1_000),
+        ];
+        assert_eq!(
+            ranks(&players),
+            [(1, "乙"), (2, "甲"), (2, "丙"), (4, "丁")]
+        );
+
+        let players = [player("甲", 0, 0, 0), player("乙", 0, 0, 0)];
+        assert_eq!(ranks(&players), [(1, "甲"), (1, "乙")]);
+    }
+
+    #[test]
+    fn split_keys_map_to_players_and_choices() {
+        assert_eq!(split_key('1', 2), Some((0, 1)));
+        assert_eq!(split_key('4', 2), Some((0, 4)));
+        assert_eq!(split_key('7', 2), Some((1, 1)));
+        assert_eq!(split_key('0', 2), Some((1, 4)));
+        // 没有第三名玩家时字母键无效
+        assert_eq!(split_key('q', 2), None);
+        assert_eq!(split_key('Q', 3), Some((2, 1)));
+        assert_eq!(split_key('p', 4), Some((3, 4)));
+        assert_eq!(split_key('5', 4), None);
+    }
+}
@@ -0,0 +1,422 @@
+use crate::file_picker::{FilePicker, Validation};
+use crate::game::PracticeMode;
+use crate::resources;
+use anyhow::{Context, Result};
+use chrono::Local;
+use crossterm::event::{self, Event, KeyCode};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Style},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+    Terminal,
+};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// 每个排行榜显示的名次数
+const TOP_COUNT: usize = 20;
+
+/// 一轮限时练习的统计
+#[derive(Debug, Clone)]
+pub struct Round {
+    started: Instant,    // 开始时间
+    limit: Duration,     // 时长
+    pub answers: usize,  // 作答次数
+    pub correct: usize,  // 答对次数(用过提示的不算)
+    pub wrong: usize,    // 答错次数
+    latencies: Vec<u64>, // 每次作答的反应时间(毫秒)
+}
+
+impl Round {
+    pub fn new(seconds: u64) -> Self {
+        Self {
+            started: Instant::now(),
+            limit: Duration::from_secs(seconds),
+            answers: 0,
+            correct: 0,
+            wrong: 0,
+            latencies: Vec::new(),
+        }
+    }
+
+    /// 记录一次作答，correct为None表示用提示答对
+    pub fn record(&mut self, correct: Option<bool>, latency_ms: Option<u64>) {
+        self.answers += 1;
+        match correct {
+            Some(true) => self.correct += 1,
+            Some(false) => self.wrong += 1,
+            None => {}
+        }
+        self.latencies.extend(latency_ms);
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.limit.saturating_sub(self.started.elapsed())
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    /// 得分：答对次数减去答错次数
+    pub fn score(&self) -> usize {
+        self.correct.saturating_sub(self.wrong)
+    }
+
+    /// 生成排行榜记录
+    pub fn entry(&self, profile: &str, scheme: &str, mode: PracticeMode) -> Entry {
+        let mut latencies = self.latencies.clone();
+        latencies.sort_unstable();
+        Entry {
+            profile: profile.to_string(),
+            scheme: scheme.to_string(),
+            mode,
+            seconds: self.limit.as_secs(),
+            score: self.score(),
+            answers: self.answers,
+            accuracy: if self.answers == 0 {
+                0.0
+            } else {
+                self.correct as f64 / self.answers as f64
+            },
+            median_ms: latencies.get(latencies.len() / 2).copied(),
+            time: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+}
+
+/// 排行榜中的一条记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub profile: String,        // 档案名称
+    pub scheme: String,         // 方案
+    pub mode: PracticeMode,     // 练习模式
+    pub seconds: u64,           // 限时秒数
+    pub score: usize,           // 得分
+    pub answers: usize,         // 作答次数
+    pub accuracy: f64,          // 正确率(0-1)
+    pub median_ms: Option<u64>, // 反应时间中位数(毫秒)
+    pub time: String,           // 完成时间
+}
+
+impl Entry {
+    /// 所属排行榜：方案、模式和时长都相同的记录放在一起比较
+    fn board(&self) -> (&str, PracticeMode, u64) {
+        (&self.scheme, self.mode, self.seconds)
+    }
+
+    /// 判断重复记录用的键：正确率是浮点数，导出再导入后可能有细微差别，不参与比较
+    fn identity(&self) -> (&str, &str, PracticeMode, u64, &str, usize) {
+        (
+            &self.profile,
+            &self.scheme,
+            self.mode,
+            self.seconds,
+            &self.time,
+            self.score,
+        )
+    }
+
+    /// 排名先比得分，再比正确率，最后比反应时间
+    fn rank_key(&self) -> (std::cmp::Reverse<usize>, std::cmp::Reverse<u64>, u64) {
+        (
+            std::cmp::Reverse(self.score),
+            std::cmp::Reverse((self.accuracy * 10000.0) as u64),
+            self.median_ms.unwrap_or(u64::MAX),
+        )
+    }
+}
+
+/// 本机所有档案共用的排行榜，保存在用户数据目录的leaderboard.json中
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Leaderboard {
+    #[serde(default)]
+    pub entries: Vec<Entry>,
+}
+
+impl Leaderboard {
+    fn path() -> PathBuf {
+        resources::user_data_dir()
+            .unwrap_or_default()
+            .join("leaderboard.json")
+    }
+
+    /// 读取排行榜，文件不存在时返回空排行榜
+    pub fn load() -> Result<Self> {
+        let path = Self::path();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Self::read(&path)
+    }
+
+    fn read(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("无法读取排行榜: {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("排行榜格式错误: {}", path.display()))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("无法保存排行榜: {}", path.display()))
+    }
+
+    /// 添加记录并保存，返回在所属排行榜中的名次(从1开始)
+    pub fn add(&mut self, entry: Entry) -> Result<usize> {
+        let rank = self.rank_of(&entry);
+        self.entries.push(entry);
+        self.save()?;
+        Ok(rank)
+    }
+
+    /// 新记录在所属排行榜中的名次，与已有记录成绩相同时排在后面
+    fn rank_of(&self, entry: &Entry) -> usize {
+        self.ranking(entry.board())
+            .iter()
+            .filter(|e| e.rank_key() <= entry.rank_key())
+            .count()
+            + 1
+    }
+
+    /// 所有排行榜，按方案、模式和时长排序
+    fn boards(&self) -> Vec<(&str, PracticeMode, u64)> {
+        let mut boards: Vec<_> = self.entries.iter().map(Entry::board).collect();
+        boards.sort_by_key(|&(scheme, mode, seconds)| {
+            let mode_idx = PracticeMode::ALL.iter().position(|m| *m == mode);
+            (scheme, mode_idx, seconds)
+        });
+        boards.dedup();
+        boards
+    }
+
+    /// 某个排行榜的记录，按名次排序
+    fn ranking(&self, board: (&str, PracticeMode, u64)) -> Vec<&Entry> {
+        let mut entries: Vec<&Entry> =
+            self.entries.iter().filter(|e| e.board() == board).collect();
+        entries.sort_by_key(|e| e.rank_key());
+        entries
+    }
+
+    /// 导出到当前目录，返回文件名
+    fn export(&self) -> Result<String> {
+        let file_name = format!("leaderboard-{}.json", Local::now().format("%Y%m%d-%H%M%S"));
+        fs::write(&file_name, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("无法导出排行榜: {}", file_name))?;
+        Ok(file_name)
+    }
+
+    /// 合并其他排行榜文件中的记录，跳过已有的记录，返回新增条数
+    fn import(&mut self, path: &str) -> Result<usize> {
+        let added = self.merge(Self::read(Path::new(path))?);
+        self.save()?;
+        Ok(added)
+    }
+
+    /// 合并记录，跳过档案、方案、模式、时长、完成时间和得分都相同的记录，返回新增条数
+    fn merge(&mut self, other: Self) -> usize {
+        let mut added = 0;
+        for entry in other.entries {
+            if !self
+                .entries
+                .iter()
+                .any(|e| e.identity() == entry.identity())
+            {
+                self.entries.push(entry);
+                added += 1;
+            }
+        }
+        added
+    }
+
+    /// 排行榜界面：←/→切换方案和模式，E导出，I导入，当前档案的记录高亮显示
+    pub fn show(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+        profile: &str,
+    ) -> Result<()> {
+        let mut board_idx = 0;
+        let mut message = String::new();
+        loop {
+            let boards = self.boards();
+            board_idx = board_idx.min(boards.len().saturating_sub(1));
+            let board = boards.get(board_idx).copied();
+            terminal.draw(|f| {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .margin(1)
+                    .constraints([
+                        Constraint::Length(3),
+                        Constraint::Min(5),
+                        Constraint::Length(3),
+                    ])
+                    .split(f.area());
+
+                let title = match board {
+                    Some((scheme, mode, seconds)) => format!(
+                        "排行榜 {}/{}: {} · {} · {}秒",
+                        board_idx + 1,
+                        boards.len(),
+                        scheme,
+                        mode.label(),
+                        seconds
+                    ),
+                    None => "排行榜".to_string(),
+                };
+                let title = Paragraph::new(title)
+                    .block(Block::default().borders(Borders::ALL))
+                    .alignment(Alignment::Center);
+                f.render_widget(title, chunks[0]);
+
+                let items: Vec<ListItem> = match board {
+                    Some(board) => self
+                        .ranking(board)
+                        .into_iter()
+                        .take(TOP_COUNT)
+                        .enumerate()
+                        .map(|(i, e)| {
+                            let median = e
+                                .median_ms
+                                .map_or("-".to_string(), |ms| format!("{:.2}秒", ms as f64 / 1000.0));
+                            let style = if e.profile == profile {
+                                Style::default().fg(Color::Yellow)
+                            } else {
+                                Style::default()
+                            };
+                            ListItem::new(format!(
+                                "{:>2}. {:<12} 得分: {:<4} 正确率: {:>3.0}%  反应时间: {:<8} {}",
+                                i + 1,
+                                e.profile,
+                                e.score,
+                                e.accuracy * 100.0,
+                                median,
+                                e.time
+                            ))
+                            .style(style)
+                        })
+                        .collect(),
+                    None => vec![ListItem::new("还没有限时练习的记录，可在设置菜单中开启限时练习")],
+                };
+                let list = List::new(items)
+                    .block(Block::default().borders(Borders::ALL).title(message.as_str()));
+                f.render_widget(list, chunks[1]);
+
+                let help = Paragraph::new("←/→: 切换排行榜 | E: 导出 | I: 导入合并 | ESC: 返回")
+                    .block(Block::default().borders(Borders::ALL))
+                    .alignment(Alignment::Center);
+                f.render_widget(help, chunks[2]);
+            })?;
+
+            if let Event::Key(key) = event::read()? {
+                #[cfg(windows)]
+                if key.kind != event::KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Left => board_idx = board_idx.saturating_sub(1),
+                    KeyCode::Right => board_idx += 1,
+                    KeyCode::Char('e') | KeyCode::Char('E') => {
+                        message = match self.export() {
+                            Ok(file_name) => format!("已导出到{}", file_name),
+                            Err(e) => format!("导出失败: {:#}", e),
+                        };
+                    }
+                    KeyCode::Char('i') | KeyCode::Char('I') => {
+                        let picker = FilePicker::new(
+                            "选择要合并的排行榜文件 (Enter确认, ESC取消)",
+                            ".",
+                            Validation::new("排行榜文件", |path| {
+                                Self::read(Path::new(path)).map(|_| ())
+                            }),
+                        );
+                        if let Some(path) = picker.run(terminal)? {
+                            message = match self.import(&path) {
+                                Ok(added) => format!("已合并{}条新记录", added),
+                                Err(e) => format!("导入失败: {:#}", e),
+                            };
+                        }
+                    }
+                    KeyCode::Esc => return Ok(()),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(profile: &str, score: usize, accuracy: f64, median_ms: Option<u64>) -> Entry {
+        Entry {
+            profile: profile.to_string(),
+            scheme: "yustar 3.8.0".to_string(),
+            mode: PracticeMode::DualCode,
+            seconds: 60,
+            score,
+            answers: 40,
+            accuracy,
+            median_ms,
+            time: "2024-03-01 12:00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn ranking_breaks_ties_by_accuracy_then_latency() {
+        let board = Leaderboard {
+            entries: vec![
+                entry("a", 30, 0.9, Some(800)),
+                entry("b", 30, 0.95, Some(900)),
+                entry("c", 30, 0.9, Some(700)),
+                entry("d", 30, 0.9, None),
+                entry("e", 31, 0.5, Some(2000)),
+            ],
+        };
+        let names: Vec<&str> = board
+            .ranking(board.entries[0].board())
+            .iter()
+            .map(|e| e.profile.as_str())
+            .collect();
+        assert_eq!(names, ["e", "b", "c", "a", "d"]);
+
+        // 成绩完全相同时排在已有记录之后
+        assert_eq!(board.rank_of(&entry("f", 30, 0.9, Some(800))), 5);
+        assert_eq!(board.rank_of(&entry("f", 30, 0.9, Some(799))), 4);
+        assert_eq!(board.rank_of(&entry("f", 32, 0.0, None)), 1);
+        // 其他排行榜的记录不参与排名
+        let other = Entry {
+            seconds: 120,
+            ..entry("f", 0, 0.0, None)
+        };
+        assert_eq!(board.rank_of(&other), 1);
+    }
+
+    #[test]
+    fn merging_an_exported_board_skips_existing_entries() {
+        let mut board = Leaderboard {
+            entries: vec![entry("a", 30, 0.1 + 0.2, Some(800))],
+        };
+        let exported = serde_json::to_string_pretty(&board).unwrap();
+        let mut other: Leaderboard = serde_json::from_str(&exported).unwrap();
+        assert_eq!(board.merge(other.clone()), 0);
+
+        // 正确率的浮点误差不影响判断重复
+        other.entries[0].accuracy = 0.3;
+        other.entries.push(Entry {
+            time: "2024-03-02 08:00:00".to_string(),
+            ..entry("a", 30, 0.3, Some(800))
+        });
+        other.entries.push(entry("b", 30, 0.3, Some(800)));
+        assert_eq!(board.merge(other.clone()), 2);
+        assert_eq!(board.entries.len(), 3);
+        assert_eq!(board.merge(other), 0);
+    }
+}
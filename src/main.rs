@@ -1,27 +1,67 @@
 use anyhow::Result;
+use cli::Command;
+use convert::{check_chaifen, convert_radicals, ConversionRules};
+use file_picker::{FilePicker, Validation};
 use crossterm::{
     event::{self, Event, KeyCode},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use game::{GameConfig, GameMode, GameState, Radical};
+use game::{GameConfig, GameMode, GameState, PracticeMode, Radical};
+use leaderboard::{Leaderboard, Round};
+use notes::Notes;
+use profile::{Mark, Profile};
+use scheme::SchemeMeta;
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Alignment, Constraint, Direction, Layout},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
     Terminal,
 };
-use std::char;
-use std::collections::HashMap;
-use std::fs::{self, File};
-use std::io::{self, BufRead, BufReader, Write};
+use std::fs;
+use std::io;
 use std::path::Path;
+use std::time::Duration;
 
+mod achievements;
+mod cli;
+mod convert;
+mod file_picker;
 mod game;
+mod hotseat;
+mod leaderboard;
+mod notes;
+mod profile;
+mod resources;
+mod scheme;
+mod settings;
+mod versus;
 
 fn main() -> Result<()> {
+    // 先处理命令行子命令，非交互命令不进入终端界面
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (profile_name, versus) = match Command::parse(&args) {
+        Ok(Command::Play { profile }) => (profile.unwrap_or_else(Profile::default_name), None),
+        Ok(Command::Versus { profile, role }) => {
+            (profile.unwrap_or_else(Profile::default_name), Some(role))
+        }
+        Ok(command) => {
+            if let Err(e) = command.run() {
+                eprintln!("错误: {:#}", e);
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Err(e) => {
+            eprintln!("错误: {}\n", e);
+            cli::print_usage();
+            std::process::exit(2);
+        }
+    };
+    let mut profile = Profile::load(&profile_name)?;
+
     // 初始化终端
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -29,25 +69,43 @@ fn main() -> Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
+    // 联网对战不读取存档，结束后直接退出
+    if let Some(role) = versus {
+        let res = versus::run(&mut terminal, role, &profile);
+        disable_raw_mode()?;
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+        return res;
+    }
+
     // 检查是否有存档
     if let Some((saved_state, saved_config)) = GameState::load_from_file() {
+        let scheme = saved_state
+            .scheme
+            .as_ref()
+            .map_or(String::new(), |meta| format!("({})", meta.display_name()));
         let continue_game = show_confirm_dialog(
             &mut terminal,
-            "检测到存档，是否从上次保存的进度继续？",
+            &format!(
+                "检测到存档{}，是否从上次保存的进度继续？\n\n{}",
+                scheme,
+                profile.progress_line()
+            ),
         )?;
         
         if continue_game {
-            let res = run_app(&mut terminal, saved_config, &mut saved_state.clone());
+            let res = run_app(&mut terminal, saved_config, &mut saved_state.clone(), &mut profile);
+            // 作答记录分批保存，练习结束时保存剩余的部分
+            let saved = profile.save();
             // 清理终端
             disable_raw_mode()?;
             execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-            return res;
+            return res.and(saved);
         }
     }
 
 
     // 显示欢迎界面
-    show_welcome(&mut terminal)?;
+    show_welcome(&mut terminal, &mut profile)?;
 
     // 显示设置菜单
     let config = GameConfig::show_settings_menu(&mut terminal)?;
@@ -59,75 +117,57 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
-    // 尝试从多个可能的位置加载资源文件
-    let mut counts_path = None;
-    let mut radical_path = None;
-
-    // 1. 首先尝试从可执行文件目录查找
-    if let Ok(exe_dir) = std::env::current_exe() {
-        if let Some(parent) = exe_dir.parent() {
-            let exe_counts = parent.join(&config.frequency_file);
-            let exe_radical = parent.join(&config.radical_file);
-
-            if exe_counts.exists() && exe_radical.exists() {
-                counts_path = Some(exe_counts);
-                radical_path = Some(exe_radical);
-            }
-        }
-    }
-
-    // 2. 如果可执行文件目录找不到，尝试从项目根目录查找
-    if counts_path.is_none() || radical_path.is_none() {
-        let project_counts = Path::new(&config.frequency_file);
-        let project_radical = Path::new(&config.radical_file);
-
-        if project_counts.exists() && project_radical.exists() {
-            counts_path = Some(project_counts.to_path_buf());
-            radical_path = Some(project_radical.to_path_buf());
-        }
-    }
-
-    // 检查是否找到有效的资源路径
-    let (counts_path, radical_path) = match (counts_path, radical_path) {
-        (Some(c), Some(r)) => (c, r),
-        _ => {
-            return Err(anyhow::anyhow!(
-                "无法找到资源文件，请确保res目录位于可执行文件目录或项目根目录下"
-            ))
-        }
-    };
-
-    // 加载字根数据
-    let radicals = Radical::load_from_files(
-        counts_path
-            .to_str()
-            .ok_or_else(|| anyhow::anyhow!("无效路径"))?,
-        radical_path
-            .to_str()
-            .ok_or_else(|| anyhow::anyhow!("无效路径"))?,
-    )?;
+    // 加载字根数据(磁盘上的文件优先，找不到时使用内置资源)
+    let radicals = Radical::load_from_files(&config.frequency_file, &config.radical_file)?;
 
     // 创建游戏状态
     let mut game_state = GameState::new(radicals, &config);
+    game_state.scheme = SchemeMeta::read_from_file(&config.radical_file);
 
     // 主游戏循环
-    let res = run_app(&mut terminal, config, &mut game_state);
+    let res = run_app(&mut terminal, config, &mut game_state, &mut profile);
+    let saved = profile.save();
 
     // 清理终端
     disable_raw_mode()?;
     execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
 
-    res
+    res.and(saved)
 }
 
 fn run_app(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     config: GameConfig,
     game_state: &mut GameState,
+    profile: &mut Profile,
 ) -> Result<()> {
     let mut input_buffer = String::new();
 
+    // 档案中已掌握和暂停的字根不参与练习
+    let scheme = profile::scheme_key(&config.radical_file, game_state.scheme.as_ref());
+    game_state.exclude(&profile.excluded(&scheme), &config);
+    game_state.notes = Notes::load(&scheme)?;
+    if game_state.is_game_over() {
+        show_message(
+            terminal,
+            "本方案的字根都已标记为已掌握或暂停，可在欢迎界面按P键恢复",
+        )?;
+        return Ok(());
+    }
+
+    // 读档后补全四选一的候选项
+    if game_state.choices.is_empty() {
+        game_state.refresh_choices(&config);
+    }
+
+    // 限时练习从进入练习界面开始计时
+    let mut round = (config.time_limit > 0).then(|| Round::new(config.time_limit));
+
     loop {
+        if let Some(round) = round.as_ref().filter(|r| r.is_over()) {
+            return finish_round(terminal, &config, game_state, profile, round);
+        }
+
         terminal.draw(|f| {
             let size = f.area();
 
@@ -144,7 +184,7 @@ fn run_app(
                 .constraints([
                     Constraint::Length(3), // 当前字根
                     Constraint::Length(3), // 输入框
-                    Constraint::Length(3), // 错误提示
+                    Constraint::Length(5), // 错误提示(之后两行为例字和笔记)
                     Constraint::Min(3),    // 键盘布局
                     Constraint::Length(3), // 统计信息
                 ])
@@ -156,19 +196,48 @@ fn run_app(
                 GameMode::Pretend => Borders::NONE,
             };
 
-            if let Some(radical) = game_state.current_radical() {
-                let radical_block = Block::default().title("当前字根").borders(border_style);
-                let radical_text = Paragraph::new(radical.text.clone())
+            if let Some((title, prompt)) = game_state.prompt(&config) {
+                let radical_block = Block::default().title(title).borders(border_style);
+                let radical_text = Paragraph::new(prompt)
                     .block(radical_block)
                     .alignment(Alignment::Center);
                 f.render_widget(radical_text, chunks[0]);
             }
 
             // 显示输入区域（增加高度）
-            let input_block = Block::default()
-                .title("输入编码 (Enter确认)")
-                .borders(border_style);
-            let input_text = Paragraph::new(input_buffer.clone())
+            let (input_title, input_content) = if config.practice_mode.is_choice() {
+                let options = game_state.choice_labels(&config);
+                ("选择答案 (按1-4)", options.join("    "))
+            } else {
+                let title = match (config.practice_mode, config.instant_feedback) {
+                    (PracticeMode::SmallCode, false) => "输入小码 (Enter确认, Tab提示)",
+                    (PracticeMode::SmallCode, true) => "输入小码 (输满自动提交, Tab提示)",
+                    (_, false) => "输入编码 (Enter确认, Tab提示)",
+                    (_, true) => "输入编码 (输满自动提交, Tab提示)",
+                };
+                (title, input_buffer.clone())
+            };
+            // 逐键反馈时把输错的字母标红
+            let input_line = match game_state.expected_answer(&config) {
+                Some(expected) if config.instant_feedback => {
+                    let expected: Vec<char> = expected.chars().collect();
+                    let spans: Vec<Span> = input_content
+                        .chars()
+                        .enumerate()
+                        .map(|(i, c)| {
+                            let ok = expected
+                                .get(i)
+                                .is_some_and(|e| c.to_lowercase().eq(e.to_lowercase()));
+                            let color = if ok { Color::Green } else { Color::Red };
+                            Span::styled(c.to_string(), Style::default().fg(color))
+                        })
+                        .collect();
+                    Line::from(spans)
+                }
+                _ => Line::from(input_content),
+            };
+            let input_block = Block::default().title(input_title).borders(border_style);
+            let input_text = Paragraph::new(input_line)
                 .block(input_block)
                 .alignment(Alignment::Center);
             f.render_widget(input_text, chunks[1]);
@@ -178,6 +247,11 @@ fn run_app(
             let error_text = if let Some(error_msg) = &game_state.last_error {
                 let style = if error_msg.starts_with("【正确】") {
                     Style::default().fg(Color::Green)
+                } else if ["【撤销】", "【已掌握】", "【暂停】", "【提示", "【笔记】"]
+                    .iter()
+                    .any(|tag| error_msg.starts_with(tag))
+                {
+                    Style::default().fg(Color::Yellow)
                 } else {
                     Style::default().fg(Color::Red)
                 };
@@ -193,21 +267,29 @@ fn run_app(
             if config.mode == GameMode::Normal {
                 let keyboard_block = Block::default().borders(Borders::NONE);
 
+                // 提示的大码键位用黄色高亮，上一个字根的大码用青色高亮
+                let hint_key = game_state.hint_key();
+                let key_style = |c: &str| {
+                    if hint_key.as_deref() == Some(c) {
+                        Style::default().fg(Color::Black).bg(Color::Yellow)
+                    } else if game_state
+                        .last_big_code
+                        .as_ref()
+                        .is_some_and(|big_code| c == big_code.to_uppercase())
+                    {
+                        Style::default().fg(Color::White).bg(Color::Cyan)
+                    } else {
+                        Style::default()
+                    }
+                };
+
                 // 创建键盘布局行
                 let mut rows: Vec<Line> = vec![];
 
                 // 第一行 QWERTYUIOP
                 let mut row1 = vec![Span::raw(" ")];
                 for c in ["Q", "W", "E", "R", "T", "Y", "U", "I", "O", "P"] {
-                    let style = if let Some(big_code) = &game_state.last_big_code {
-                        if c == big_code.to_uppercase() {
-                            Style::default().fg(Color::White).bg(Color::Cyan)
-                        } else {
-                            Style::default()
-                        }
-                    } else {
-                        Style::default()
-                    };
+                    let style = key_style(c);
                     row1.push(Span::styled(format!("[{}]", c), style));
                     row1.push(Span::raw(" "));
                 }
@@ -216,15 +298,7 @@ fn run_app(
                 // 第二行 ASDFGHJKL
                 let mut row2 = vec![Span::raw(" ")];
                 for c in ["A", "S", "D", "F", "G", "H", "J", "K", "L"] {
-                    let style = if let Some(big_code) = &game_state.last_big_code {
-                        if c == big_code.to_uppercase() {
-                            Style::default().fg(Color::White).bg(Color::Cyan)
-                        } else {
-                            Style::default()
-                        }
-                    } else {
-                        Style::default()
-                    };
+                    let style = key_style(c);
                     row2.push(Span::styled(format!("[{}]", c), style));
                     row2.push(Span::raw(" "));
                 }
@@ -234,15 +308,7 @@ fn run_app(
                 // 第三行 ZXCVBNM
                 let mut row3 = vec![Span::raw(" ")];
                 for c in ["Z", "X", "C", "V", "B", "N", "M"] {
-                    let style = if let Some(big_code) = &game_state.last_big_code {
-                        if c == big_code.to_uppercase() {
-                            Style::default().fg(Color::White).bg(Color::Cyan)
-                        } else {
-                            Style::default()
-                        }
-                    } else {
-                        Style::default()
-                    };
+                    let style = key_style(c);
                     row3.push(Span::styled(format!("[{}]", c), style));
                     row3.push(Span::raw(" "));
                 }
@@ -260,20 +326,61 @@ fn run_app(
             #[cfg(target_os = "macos")]
             let quit_key = "ESC/Control+Q";
             // 显示进度和统计
+            let adaptive = if config.adaptive_target > 0 {
+                format!(
+                    " | 近期正确率: {:.0}% | 惩罚: {}次",
+                    game_state.rolling_accuracy().unwrap_or(0.0) * 100.0,
+                    game_state.effective_penalty(&config)
+                )
+            } else {
+                String::new()
+            };
+            let undo = if game_state.can_undo() && round.is_none() {
+                " | 撤销: Ctrl+Z"
+            } else {
+                ""
+            };
+            let timer = round.as_ref().map_or(String::new(), |r| {
+                format!(" | 剩余: {}秒 | 得分: {}", r.remaining().as_secs(), r.score())
+            });
             let stats = format!(
-                "进度: {}/{} | 正确: {} | 错误: {} | 退出: {}",
+                "进度: {}/{} | 正确: {} | 错误: {}{}{}{} | 掌握/暂停: Ctrl+K/Ctrl+S | 笔记: Ctrl+E | 退出: {}",
                 game_state.progress().0,
                 game_state.progress().1,
                 game_state.correct_count,
                 game_state.wrong_count,
+                timer,
+                adaptive,
+                undo,
                 quit_key
             );
             let stats_block = Block::default().title("统计信息").borders(border_style);
             let stats_text = Paragraph::new(stats).block(stats_block);
             f.render_widget(stats_text, chunks[4]);
+
+            // 新达成的成就浮在键盘区域上方
+            if let Some(toast) = &game_state.toast {
+                let area = chunks[3];
+                let width = (toast.chars().count() as u16 * 2 + 4).min(area.width);
+                let rect = Rect {
+                    x: area.x + (area.width - width) / 2,
+                    y: area.y,
+                    width,
+                    height: 3.min(area.height),
+                };
+                let toast = Paragraph::new(toast.as_str())
+                    .style(Style::default().fg(Color::Yellow))
+                    .alignment(Alignment::Center)
+                    .block(Block::default().borders(Borders::ALL).title("成就"));
+                f.render_widget(Clear, rect);
+                f.render_widget(toast, rect);
+            }
         })?;
 
-        // 处理用户输入
+        // 处理用户输入，限时练习时定时刷新倒计时
+        if round.is_some() && !event::poll(Duration::from_millis(250))? {
+            continue;
+        }
         if let Event::Key(key) = event::read()? {
             #[cfg(windows)]
             if key.kind != event::KeyEventKind::Press {
@@ -288,38 +395,91 @@ fn run_app(
                 KeyCode::Char('q') if key.modifiers.contains(event::KeyModifiers::CONTROL) => {
                     return Ok(());
                 }
+                KeyCode::Char('z') if key.modifiers.contains(event::KeyModifiers::CONTROL) => {
+                    // 撤销上一次作答(手滑输错时使用)，限时练习中不能撤销
+                    if round.is_some() {
+                        game_state.last_error = Some("【撤销】限时练习中不能撤销".to_string());
+                    } else if game_state.undo_last_answer() {
+                        // 今日练习记录和这次作答达成的成就也一并撤销
+                        profile.undo_last_answer()?;
+                        game_state.toast = None;
+                    }
+                    input_buffer.clear();
+                }
+                KeyCode::Char('k') if key.modifiers.contains(event::KeyModifiers::CONTROL) => {
+                    let finished =
+                        mark_current(terminal, &config, game_state, profile, &scheme, Mark::Known)?;
+                    if finished {
+                        return Ok(());
+                    }
+                    input_buffer.clear();
+                }
+                KeyCode::Char('s') if key.modifiers.contains(event::KeyModifiers::CONTROL) => {
+                    let finished = mark_current(
+                        terminal,
+                        &config,
+                        game_state,
+                        profile,
+                        &scheme,
+                        Mark::Suspended,
+                    )?;
+                    if finished {
+                        return Ok(());
+                    }
+                    input_buffer.clear();
+                }
+                KeyCode::Char('e') if key.modifiers.contains(event::KeyModifiers::CONTROL) => {
+                    // 编辑当前字根的笔记和大码键位的口诀
+                    if let Some(radical) = game_state.current_radical().cloned() {
+                        let note = game_state.notes.radical_note(&radical).unwrap_or_default();
+                        let key_note = game_state.notes.key_note(&radical).unwrap_or_default();
+                        if let Some((note, key_note)) =
+                            show_note_editor(terminal, &radical, note, key_note)?
+                        {
+                            game_state.notes.set(&radical, &note, &key_note)?;
+                            game_state.last_error = Some(format!("【笔记】已保存“{}”的笔记", radical.text));
+                        }
+                    }
+                }
+                KeyCode::Char(c) if config.practice_mode.is_choice() => {
+                    // 四选一模式下按数字键直接提交
+                    let input = c.to_string();
+                    let finished =
+                        submit_answer(terminal, &config, game_state, profile, round.as_mut(), &input)?;
+                    if finished {
+                        return Ok(());
+                    }
+                }
                 KeyCode::Char(c) => {
                     input_buffer.push(c);
+                    // 逐键反馈模式下输满编码长度自动提交，不需要按Enter
+                    if game_state.input_complete(&input_buffer, &config) {
+                        let input = std::mem::take(&mut input_buffer);
+                        let finished =
+                            submit_answer(terminal, &config, game_state, profile, round.as_mut(), &input)?;
+                        if finished {
+                            return Ok(());
+                        }
+                    }
                 }
                 KeyCode::Backspace => {
                     input_buffer.pop();
                 }
-                KeyCode::Enter => {
-                    if !input_buffer.is_empty() {
-                        let is_correct = game_state.check_input(&input_buffer, &config);
-                        input_buffer.clear();
-
-                        // 根据结果给出反馈
-                        if is_correct {
-                            // 正确，检查是否需要切换到下一个字根
-                            if !game_state.next_radical(&config) && game_state.is_game_over() {
-                                // 游戏结束
-                                let _ = show_message(terminal, "恭喜完成所有练习!");
-                                // 删除存档文件
-                                if Path::new("save.json").exists() {
-                                    fs::remove_file("save.json")?;
-                                }
-                                return Ok(());
-                            }
-                        } else if let Some(_radical) = game_state.current_radical() {
-                        } else {
-                            game_state.last_error = None;
-                        }
+                KeyCode::Tab => {
+                    game_state.request_hint(&config);
+                }
+                KeyCode::Enter if !input_buffer.is_empty() => {
+                    let input = std::mem::take(&mut input_buffer);
+                    let finished =
+                        submit_answer(terminal, &config, game_state, profile, round.as_mut(), &input)?;
+                    if finished {
+                        return Ok(());
                     }
                 }
                 KeyCode::Esc => {
-                    // 询问是否保存进度
-                    let save = show_confirm_dialog(terminal, "是否保存当前进度？")?;
+                    // 询问是否保存进度，限时练习不保存
+                    let save = round.is_none()
+                        && show_confirm_dialog(terminal, "是否保存当前进度？")?;
                     if save {
                         game_state.save_to_file(&config)?;
                     }
@@ -333,13 +493,126 @@ fn run_app(
     }
 }
 
-fn show_welcome(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
+/// 提交一次作答，返回练习是否已全部完成
+fn submit_answer(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    config: &GameConfig,
+    game_state: &mut GameState,
+    profile: &mut Profile,
+    mut round: Option<&mut Round>,
+    input: &str,
+) -> Result<bool> {
+    let answered = game_state.total_practice;
+    let is_correct = game_state.check_input(input, config);
+    game_state.toast = None;
+
+    // 计入作答的记到今天的练习记录中，并检查成就
+    if game_state.total_practice > answered {
+        let latency_ms = game_state.recent_results.back().and_then(|r| r.latency_ms);
+        profile.record_answer(is_correct, latency_ms)?;
+        if let Some(round) = round.as_deref_mut() {
+            // 用过提示答对的不计分
+            let clean = game_state.recent_results.back().is_some_and(|r| r.correct);
+            let result = match (is_correct, clean) {
+                (false, _) => Some(false),
+                (true, true) => Some(true),
+                (true, false) => None,
+            };
+            round.record(result, latency_ms);
+        }
+        let unlocked = achievements::check(game_state, profile)?;
+        game_state.toast = achievements::toast_text(&unlocked);
+    }
+
+    // 根据结果给出反馈
+    if is_correct {
+        // 正确，检查是否需要切换到下一个字根
+        if !game_state.next_radical(config) && game_state.is_game_over() {
+            if let Some(toast) = game_state.toast.take() {
+                let _ = show_message(terminal, &toast);
+            }
+            finish_practice(terminal)?;
+            if let Some(round) = round {
+                finish_round(terminal, config, game_state, profile, round)?;
+            }
+            return Ok(true);
+        }
+    } else if game_state.current_radical().is_none() {
+        game_state.last_error = None;
+    }
+    Ok(false)
+}
+
+/// 把当前字根标记为已掌握或暂停并移出练习，返回练习是否已全部完成
+fn mark_current(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    config: &GameConfig,
+    game_state: &mut GameState,
+    profile: &mut Profile,
+    scheme: &str,
+    mark: Mark,
+) -> Result<bool> {
+    let Some(text) = game_state.current_radical().map(|r| r.text.clone()) else {
+        return Ok(false);
+    };
+    profile.mark(scheme, &text, mark);
+    profile.save()?;
+    game_state.exclude([&text], config);
+    if game_state.is_game_over() {
+        finish_practice(terminal)?;
+        return Ok(true);
+    }
+    let hint = match mark {
+        Mark::Known => "以后不再出现",
+        Mark::Suspended => "一周后自动恢复，也可在欢迎界面按P键提前恢复",
+    };
+    game_state.last_error = Some(format!("【{}】“{}”已移出练习，{}", mark.label(), text, hint));
+    Ok(false)
+}
+
+/// 限时练习结束：成绩计入排行榜并显示名次
+fn finish_round(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    config: &GameConfig,
+    game_state: &GameState,
+    profile: &Profile,
+    round: &Round,
+) -> Result<()> {
+    let scheme = profile::scheme_key(&config.radical_file, game_state.scheme.as_ref());
+    let entry = round.entry(&profile.name, &scheme, config.practice_mode);
+    let median = entry
+        .median_ms
+        .map_or("-".to_string(), |ms| format!("{:.2}秒", ms as f64 / 1000.0));
+    let summary = format!(
+        "限时练习结束! 得分: {} | 正确率: {:.0}% | 反应时间中位数: {}",
+        entry.score,
+        entry.accuracy * 100.0,
+        median
+    );
+    let rank = Leaderboard::load()?.add(entry)?;
+    let _ = show_message(terminal, &format!("{} | 排行榜第{}名", summary, rank));
+    Ok(())
+}
+
+/// 全部练习完成：提示并删除存档
+fn finish_practice(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
+    let _ = show_message(terminal, "恭喜完成所有练习!");
+    if Path::new("save.json").exists() {
+        fs::remove_file("save.json")?;
+    }
+    Ok(())
+}
+
+fn show_welcome(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    profile: &mut Profile,
+) -> Result<()> {
     terminal.draw(|f| {
         let size = f.area();
         let block = Block::default()
             .title("宇浩字根练习")
             .borders(Borders::ALL);
-        let welcome_text = Paragraph::new(vec![
+        let mut lines = vec![
             Line::from("欢迎使用宇浩字根练习工具"),
             Line::from(""),
             Line::from(Span::styled(
@@ -349,9 +622,24 @@ fn show_welcome(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result
             Line::from(""),
             Line::from("按任意键继续..."),
             Line::from("按 Z 键进入字根编码转换..."),
-        ])
-        .block(block)
-        .alignment(Alignment::Center);
+            Line::from("按 P 键管理已掌握/暂停的字根..."),
+            Line::from("按 G 键设置每日目标..."),
+            Line::from("按 A 键查看成就..."),
+            Line::from("按 L 键查看限时练习排行榜..."),
+            Line::from("按 H 键进入多人轮流对战..."),
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("档案: {} | 目标: {}", profile.name, profile.goal.label()),
+                Style::default().fg(Color::Gray),
+            )),
+            Line::from(profile.progress_line()),
+            Line::from(""),
+        ];
+        // 练习日历热力图
+        lines.extend(profile.heatmap());
+        let welcome_text = Paragraph::new(lines)
+            .block(block)
+            .alignment(Alignment::Center);
         f.render_widget(welcome_text, size);
     })?;
 
@@ -362,7 +650,27 @@ fn show_welcome(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result
                 continue;
             }
             if key.code == KeyCode::Char('z') || key.code == KeyCode::Char('Z') {
-                return show_conversion_ui(terminal);
+                return show_conversion_ui(terminal, profile);
+            }
+            if key.code == KeyCode::Char('p') || key.code == KeyCode::Char('P') {
+                profile.show_manager(terminal)?;
+                return show_welcome(terminal, profile);
+            }
+            if key.code == KeyCode::Char('g') || key.code == KeyCode::Char('G') {
+                profile.show_goal_settings(terminal)?;
+                return show_welcome(terminal, profile);
+            }
+            if key.code == KeyCode::Char('a') || key.code == KeyCode::Char('A') {
+                achievements::show_list(terminal, profile)?;
+                return show_welcome(terminal, profile);
+            }
+            if key.code == KeyCode::Char('l') || key.code == KeyCode::Char('L') {
+                Leaderboard::load()?.show(terminal, &profile.name)?;
+                return show_welcome(terminal, profile);
+            }
+            if key.code == KeyCode::Char('h') || key.code == KeyCode::Char('H') {
+                hotseat::run(terminal)?;
+                return show_welcome(terminal, profile);
             }
             break;
         }
@@ -370,11 +678,14 @@ fn show_welcome(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result
     Ok(())
 }
 
-fn show_conversion_ui(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
+fn show_conversion_ui(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    profile: &mut Profile,
+) -> Result<()> {
     let mut input_fields = vec![
         (String::from("./yustar_chaifen.dict.yaml"), 0), // (文本内容, 光标位置)
-        (String::from("res/yucode-custom.txt"), 0),
-        (String::from("res/counts-custom.txt"), 0),
+        (resources::user_data_path("yucode-custom.txt"), 0),
+        (resources::user_data_path("counts-custom.txt"), 0),
     ];
     // 初始化光标位置到末尾
     for (text, pos) in &mut input_fields {
@@ -415,7 +726,7 @@ fn show_conversion_ui(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) ->
                 .map(|(i, (text, pos))| {
                     let mut spans = Vec::new();
                     let label = match i {
-                        0 => "拆分表文件: ",
+                        0 => "拆分表文件(Tab浏览): ",
                         1 => "编码文件: ",
                         2 => "频率文件: ",
                         _ => "",
@@ -558,21 +869,49 @@ fn show_conversion_ui(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) ->
                 KeyCode::Enter => {
                     match focus_state {
                         FocusState::Button(true) => {
-                            // 确认按钮被选中 - 执行转换后直接退出
-                            convert_radicals(
+                            // 确认按钮被选中 - 执行转换，诊断报告写到编码文件旁边
+                            let rules = ConversionRules::load(None)?;
+                            let summary = convert_radicals(
                                 &input_fields[0].0,
                                 &input_fields[1].0,
                                 &input_fields[2].0,
+                                &rules,
+                                None,
+                            )?;
+                            let report_path = format!("{}.log", input_fields[1].0);
+                            fs::write(&report_path, summary.report.to_string())?;
+                            show_message(
+                                terminal,
+                                &format!(
+                                    "转换完成：导出{}个字根编码，{}\n诊断报告: {}\n\n按Enter返回",
+                                    summary.codes,
+                                    summary.report.brief(),
+                                    report_path
+                                ),
                             )?;
-                            return show_welcome(terminal);
+                            return show_welcome(terminal, profile);
                         }
                         FocusState::Button(false) => {
                             // 取消按钮被选中 - 返回欢迎界面
-                            return show_welcome(terminal);
+                            return show_welcome(terminal, profile);
                         }
                         _ => {}
                     }
                 }
+                KeyCode::Tab => {
+                    // 在拆分表输入框上打开文件浏览器
+                    if let FocusState::InputField(0) = focus_state {
+                        let picker = FilePicker::new(
+                            "选择拆分表文件 (Enter确认, ESC取消)",
+                            &input_fields[0].0,
+                            Validation::new("拆分表文件", check_chaifen),
+                        );
+                        if let Some(path) = picker.run(terminal)? {
+                            input_fields[0].1 = path.len();
+                            input_fields[0].0 = path;
+                        }
+                    }
+                }
                 KeyCode::Char(c) => {
                     if let FocusState::InputField(idx) = focus_state {
                         let (text, pos) = &mut input_fields[idx];
@@ -590,7 +929,7 @@ fn show_conversion_ui(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) ->
                     }
                 }
                 KeyCode::Esc => {
-                    return show_welcome(terminal);
+                    return show_welcome(terminal, profile);
                 }
                 _ => {}
             }
@@ -598,198 +937,6 @@ fn show_conversion_ui(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) ->
     }
 }
 
-fn convert_radicals(
-    input_path: &str,
-    code_output_path: &str,
-    counts_output_path: &str,
-) -> Result<()> {
-    // 检查输入文件是否存在
-    if !Path::new(input_path).exists() {
-        return Err(anyhow::anyhow!("拆分表文件不存在: {}", input_path));
-    }
-
-    // 读取输入文件
-    let file = File::open(input_path)?;
-    let reader = BufReader::new(file);
-
-    let mut radical_counts: HashMap<String, u32> = HashMap::new();
-    let mut radical_codes: HashMap<String, String> = HashMap::new();
-    let mut processing = false;
-    let mut is_sun_moon = false;
-
-    for line in reader.lines() {
-        let line = line?;
-        let line = line.trim();
-
-        // 跳过注释行和空行
-        if line.is_empty() || line.starts_with('#') {
-            if !is_sun_moon && (line.starts_with("# 日月") || line.starts_with("# 宇浩日月"))
-            {
-                is_sun_moon = true
-            }
-            continue;
-        }
-
-        // 检查是否到达"..."行
-        if !processing && line.starts_with("...") {
-            processing = true;
-            continue;
-        }
-
-        if processing {
-            // 解析行格式：汉字\t[拆分,编码,拼音,字符集,unicode]
-            let tab_start = line.find('\t').unwrap_or(2);
-            if let Some(bracket_start) = line[tab_start..].find('[').map(|i| i + tab_start) {
-                if let Some(bracket_end) = line[tab_start..].find(']').map(|i| i + tab_start) {
-                    let content = &line[bracket_start + 1..bracket_end];
-                    let counting = content.contains("CJK");
-                    let parts: Vec<&str> = content.split(',').collect();
-                    if parts.len() >= 2 {
-                        let radicals = parts[0].trim(); // 拆分部分
-                        let codes = parts[1].trim(); // 编码部分
-
-                        if radicals.is_empty() {
-                            continue;
-                        }
-
-                        // 处理拆分和编码
-                        let radical_list = extract_radicals(radicals);
-                        let code_list = extract_codes(codes, is_sun_moon);
-
-                        if radical_list.len() == code_list.len() {
-                            let mut i = 0;
-                            for (radical, code) in radical_list.iter().zip(code_list.iter()) {
-                                if counting {
-                                    // 统计字根出现次数
-                                    *radical_counts.entry(radical.to_string()).or_insert(0) += 1;
-                                }
-
-                                i += 1;
-                                if i < 4 && code_list.len() == i {
-                                    if !radical_codes.contains_key(radical)
-                                        || radical_codes.get(radical).map_or("", |v| v).len()
-                                            < code.len()
-                                    {
-                                        // 记录字根编码
-                                        radical_codes.insert(radical.to_string(), code.to_string());
-                                        // 特殊处理"曾中"字根，使用"横日"的编码
-                                        if radical == "{横日}" {
-                                            radical_codes
-                                                .insert("{曾中}".to_string(), code.to_string());
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    // 按编码排序并写入编码文件
-    let mut sorted_codes: Vec<(&String, &String)> = radical_codes.iter().collect();
-    sorted_codes.sort_by(|a, b| a.1.cmp(b.1));
-
-    let mut code_file = File::create(code_output_path)?;
-    for (radical, code) in sorted_codes {
-        writeln!(code_file, "{} {}", code.trim(), radical.trim())?;
-    }
-
-    // 按频率排序并写入频率文件
-    let mut sorted_counts: Vec<(&String, &u32)> = radical_counts.iter().collect();
-    sorted_counts.sort_by(|a, b| b.1.cmp(a.1));
-
-    let mut counts_file = File::create(counts_output_path)?;
-    for (radical, count) in sorted_counts {
-        writeln!(counts_file, "{} {}", radical.trim(), count)?;
-    }
-
-    Ok(())
-}
-
-fn extract_radicals(radicals: &str) -> Vec<String> {
-    let mut result = Vec::new();
-    let mut current = String::new();
-    let mut in_brackets = false;
-
-    for c in radicals.chars() {
-        match c {
-            '{' => {
-                in_brackets = true;
-                current.clear();
-                current.push(c);
-            }
-            '}' => {
-                in_brackets = false;
-                if !current.is_empty() {
-                    current.push(c);
-                    result.push(current.clone());
-                    current.clear();
-                }
-            }
-            _ => {
-                if in_brackets {
-                    current.push(c);
-                } else {
-                    result.push(c.to_string());
-                }
-            }
-        }
-    }
-
-    result
-}
-
-fn extract_codes(codes: &str, is_sun_moon: bool) -> Vec<String> {
-    let mut result = Vec::new();
-    let mut current = String::new();
-    let mut in_code = false;
-
-    for c in codes.chars() {
-        if c.is_uppercase() {
-            // 大写字母开始新编码
-            if !current.is_empty() {
-                result.push(current.clone());
-                current.clear();
-            }
-            current.push(c.to_ascii_lowercase());
-            in_code = true;
-        } else if c.is_lowercase() && in_code {
-            // 小写字母继续当前编码
-            if c.is_ascii() {
-                current.push(c);
-            } else {
-                current.push(char::from_u32((c as u32) - 9327).unwrap())
-            }
-        } else {
-            // 其他字符结束当前编码
-            if !current.is_empty() {
-                result.push(current.clone());
-                current.clear();
-                in_code = false;
-            }
-        }
-    }
-
-    // 添加最后一个编码
-    if !current.is_empty() {
-        result.push(current);
-    }
-
-    // 如果编码长度>2，只取前两个字母
-    result
-        .iter()
-        .map(|code| {
-            if !is_sun_moon && code.len() > 2 {
-                code[..2].to_string()
-            } else {
-                code.clone()
-            }
-        })
-        .collect()
-}
-
 fn show_confirm_dialog(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     message: &str,
@@ -797,11 +944,10 @@ fn show_confirm_dialog(
     terminal.draw(|f| {
         let size = f.area();
         let block = Block::default().title("确认").borders(Borders::ALL);
-        let text = Paragraph::new(vec![
-            Line::from(message),
-            Line::from(""),
-            Line::from("按Y确认，按N取消"),
-        ])
+        let mut lines: Vec<Line> = message.lines().map(Line::from).collect();
+        lines.push(Line::from(""));
+        lines.push(Line::from("按Y确认，按N取消"));
+        let text = Paragraph::new(lines)
         .block(block)
         .alignment(Alignment::Center);
         f.render_widget(text, size);
@@ -822,6 +968,72 @@ fn show_confirm_dialog(
     }
 }
 
+/// 编辑字根笔记和大码键位的口诀，Tab切换输入框，Enter保存，ESC取消；内容为空时删除
+fn show_note_editor(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    radical: &Radical,
+    note: String,
+    key_note: String,
+) -> Result<Option<(String, String)>> {
+    let key = radical.big_code.to_uppercase();
+    let mut fields = [note, key_note];
+    let mut active = 0;
+    loop {
+        terminal.draw(|f| {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .margin(1)
+                .constraints([
+                    Constraint::Length(3),
+                    Constraint::Length(3),
+                    Constraint::Length(3),
+                    Constraint::Min(0),
+                ])
+                .split(f.area());
+            let title = Paragraph::new(format!("编辑“{}”的笔记", radical.text))
+                .block(Block::default().borders(Borders::ALL))
+                .alignment(Alignment::Center);
+            f.render_widget(title, chunks[0]);
+            let titles = [
+                "笔记 (Enter保存, Tab切换, ESC取消, 清空后保存即删除)".to_string(),
+                format!("{}键口诀 (同一键位的字根共用)", key),
+            ];
+            for (i, (text, title)) in fields.iter().zip(titles).enumerate() {
+                let mut spans = vec![Span::raw(text.as_str())];
+                let mut block = Block::default().borders(Borders::ALL).title(title);
+                if i == active {
+                    spans.push(Span::styled(" ", Style::default().bg(Color::White)));
+                    block = block.border_style(Style::default().fg(Color::Yellow));
+                }
+                f.render_widget(
+                    Paragraph::new(Line::from(spans)).block(block),
+                    chunks[i + 1],
+                );
+            }
+        })?;
+
+        if let Event::Key(key) = event::read()? {
+            #[cfg(windows)]
+            if key.kind != event::KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char(c) => fields[active].push(c),
+                KeyCode::Backspace => {
+                    fields[active].pop();
+                }
+                KeyCode::Tab | KeyCode::Up | KeyCode::Down => active = 1 - active,
+                KeyCode::Enter => {
+                    let [note, key_note] = fields;
+                    return Ok(Some((note, key_note)));
+                }
+                KeyCode::Esc => return Ok(None),
+                _ => {}
+            }
+        }
+    }
+}
+
 fn show_message(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     message: &str,
@@ -0,0 +1,205 @@
+use crate::game::Radical;
+use crate::resources;
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+const NOTES_HEADER: &str = "\
+# 字根笔记：每行\"字根 笔记\"，只有字根没有笔记的行表示不显示字根文件中的笔记
+# @开头的行是该键位的口诀，如\"@F 口诀内容\"
+";
+
+/// 字根笔记和键位口诀，按方案保存在用户数据目录的notes目录下
+#[derive(Debug, Clone, Default)]
+pub struct Notes {
+    path: PathBuf,                      // 笔记文件路径
+    radicals: BTreeMap<String, String>, // 字根 -> 笔记
+    keys: BTreeMap<char, String>,       // 大码键位 -> 口诀
+}
+
+impl Notes {
+    /// 读取方案的笔记文件，文件不存在时返回空笔记
+    pub fn load(scheme: &str) -> Result<Self> {
+        // 方案键可能是文件路径，转换成可用作文件名的形式
+        let file_name = resources::safe_file_name(scheme);
+        let path = resources::user_data_dir()
+            .unwrap_or_default()
+            .join("notes")
+            .join(format!("{}.txt", file_name));
+        let mut notes = Self {
+            path,
+            ..Self::default()
+        };
+        let content = match fs::read_to_string(&notes.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(notes),
+            Err(e) => {
+                return Err(e).with_context(|| format!("无法读取笔记: {}", notes.path.display()))
+            }
+        };
+        notes.parse(&content);
+        Ok(notes)
+    }
+
+    /// 解析笔记文件内容
+    fn parse(&mut self, content: &str) {
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, text) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let text = text.trim().to_string();
+            if let Some(key) = name.strip_prefix('@') {
+                let mut chars = key.chars();
+                if let (Some(key), None) = (chars.next(), chars.next()) {
+                    if key.is_ascii_alphabetic() && !text.is_empty() {
+                        self.keys.insert(key.to_ascii_uppercase(), text);
+                    }
+                }
+            } else {
+                self.radicals.insert(name.to_string(), text);
+            }
+        }
+    }
+
+    /// 笔记文件内容，口诀在前
+    fn render(&self) -> String {
+        let mut content = NOTES_HEADER.to_string();
+        for (key, text) in &self.keys {
+            content.push_str(&format!("@{} {}\n", key, text));
+        }
+        for (radical, text) in &self.radicals {
+            if text.is_empty() {
+                content.push_str(&format!("{}\n", radical));
+            } else {
+                content.push_str(&format!("{} {}\n", radical, text));
+            }
+        }
+        content
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.path, self.render())
+            .with_context(|| format!("无法保存笔记: {}", self.path.display()))
+    }
+
+    /// 字根自己的笔记：笔记文件优先(清空过的不再显示字根文件中的笔记)，其次是字根文件中的注释列
+    pub fn radical_note(&self, radical: &Radical) -> Option<String> {
+        let text = self.radicals.get(&radical.text).unwrap_or(&radical.note);
+        (!text.is_empty()).then(|| text.clone())
+    }
+
+    /// 字根大码键位的口诀
+    pub fn key_note(&self, radical: &Radical) -> Option<String> {
+        let key = radical.big_code.chars().next()?.to_ascii_uppercase();
+        self.keys.get(&key).cloned()
+    }
+
+    /// 显示用的笔记，包含大码键位的口诀，如"口诀(F): …；笔记: …"
+    pub fn describe(&self, radical: &Radical) -> Option<String> {
+        let key = radical.big_code.chars().next()?.to_ascii_uppercase();
+        let parts: Vec<String> = [
+            self.keys.get(&key).map(|text| format!("口诀({}): {}", key, text)),
+            self.radical_note(radical).map(|text| format!("笔记: {}", text)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        (!parts.is_empty()).then(|| parts.join("；"))
+    }
+
+    /// 修改字根的笔记和大码键位的口诀并保存，内容为空时删除
+    pub fn set(&mut self, radical: &Radical, note: &str, key_note: &str) -> Result<()> {
+        self.update(radical, note, key_note);
+        self.save()
+    }
+
+    fn update(&mut self, radical: &Radical, note: &str, key_note: &str) {
+        let note = note.trim();
+        if note.is_empty() && radical.note.is_empty() {
+            self.radicals.remove(&radical.text);
+        } else {
+            // 清空字根文件中已有的笔记时保留空行，读取时不再显示原来的笔记
+            self.radicals.insert(radical.text.clone(), note.to_string());
+        }
+        if let Some(key) = radical.big_code.chars().next() {
+            let key = key.to_ascii_uppercase();
+            let key_note = key_note.trim();
+            if key_note.is_empty() {
+                self.keys.remove(&key);
+            } else {
+                self.keys.insert(key, key_note.to_string());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn radical(text: &str, code: &str, note: &str) -> Radical {
+        Radical {
+            code: code.to_string(),
+            text: text.to_string(),
+            frequency: 0,
+            big_code: code[..1].to_string(),
+            small_code: code[1..].to_string(),
+            examples: String::new(),
+            note: note.to_string(),
+        }
+    }
+
+    #[test]
+    fn single_letter_radical_is_not_a_key_note() {
+        let mut notes = Notes::default();
+        notes.parse("# 注释\n@F 口诀\nF 字母字根\n@x 小写也算\n@FG 无效\n");
+        let letter = radical("F", "fa", "");
+        assert_eq!(notes.radical_note(&letter).as_deref(), Some("字母字根"));
+        assert_eq!(notes.key_note(&letter).as_deref(), Some("口诀"));
+        assert_eq!(notes.keys.get(&'X').map(String::as_str), Some("小写也算"));
+        assert_eq!(notes.keys.len(), 2);
+    }
+
+    #[test]
+    fn notes_round_trip() {
+        let mut notes = Notes::default();
+        let kou = radical("口", "ko", "方框");
+        let f = radical("F", "fa", "");
+        notes.update(&f, "字母字根", "土士二干");
+        notes.update(&kou, "", "口");
+        notes.update(&radical("木", "mu", ""), "十字", "");
+
+        let mut loaded = Notes::default();
+        loaded.parse(&notes.render());
+        assert_eq!(loaded.radicals, notes.radicals);
+        assert_eq!(loaded.keys, notes.keys);
+        assert_eq!(loaded.radical_note(&f).as_deref(), Some("字母字根"));
+        assert_eq!(loaded.key_note(&f).as_deref(), Some("土士二干"));
+        // 清空后不再显示字根文件中的笔记
+        assert_eq!(loaded.radical_note(&kou), None);
+        assert_eq!(loaded.describe(&kou).as_deref(), Some("口诀(K): 口"));
+    }
+
+    #[test]
+    fn clearing_notes_removes_entries() {
+        let mut notes = Notes::default();
+        let mu = radical("木", "mu", "");
+        notes.update(&mu, "十字", "木");
+        notes.update(&mu, " ", "");
+        assert!(notes.radicals.is_empty());
+        assert!(notes.keys.is_empty());
+
+        // 字根文件中的笔记在没有覆盖时照常显示
+        assert_eq!(
+            notes.radical_note(&radical("口", "ko", "方框")).as_deref(),
+            Some("方框")
+        );
+    }
+}
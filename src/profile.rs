@@ -0,0 +1,688 @@
+use crate::resources;
+use crate::scheme::SchemeMeta;
+use crate::settings::{Field, SettingsForm};
+use anyhow::{Context, Result};
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use crossterm::event::{self, Event, KeyCode};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Terminal,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// 单次作答计入练习时长的上限(秒)，超过视为离开
+const MAX_ANSWER_SECONDS: u64 = 60;
+/// 热力图显示的周数
+const HEATMAP_WEEKS: i64 = 16;
+/// 每累计多少次作答保存一次档案，其余的在练习结束时保存
+const SAVE_INTERVAL: usize = 20;
+/// 暂停的字根自动恢复练习前的天数
+const SUSPEND_DAYS: i64 = 7;
+
+/// 用户档案：跨练习保留的个人数据，按名称保存在用户数据目录的profiles目录下
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Profile {
+    #[serde(skip)]
+    pub name: String,                              // 档案名称
+    #[serde(default)]
+    pub schemes: BTreeMap<String, SchemeProgress>, // 按方案区分的字根标记
+    #[serde(default)]
+    pub goal: DailyGoal,                           // 每日目标
+    #[serde(default)]
+    pub days: BTreeMap<String, DayRecord>,         // 每天的练习记录(日期为YYYY-MM-DD)
+    #[serde(default)]
+    pub achievements: BTreeMap<String, String>,    // 已达成的成就 -> 达成日期
+    #[serde(skip)]
+    last_answer: Option<AnswerEffect>,             // 最近一次作答的记录，撤销作答时还原
+    #[serde(skip)]
+    unsaved: usize,                                // 还没有保存的作答次数
+}
+
+/// 一次作答对档案的改动
+#[derive(Debug, Clone)]
+struct AnswerEffect {
+    date: String,          // 记录到的日期
+    correct: bool,         // 是否答对
+    seconds: u64,          // 计入的练习时长(秒)
+    unlocked: Vec<String>, // 这次作答达成的成就
+}
+
+/// 每日练习目标
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DailyGoal {
+    Answers(usize), // 每天作答次数
+    Minutes(u64),   // 每天练习分钟数
+}
+
+impl Default for DailyGoal {
+    fn default() -> Self {
+        DailyGoal::Answers(200)
+    }
+}
+
+impl DailyGoal {
+    pub fn label(self) -> String {
+        match self {
+            DailyGoal::Answers(n) => format!("每天作答{}次", n),
+            DailyGoal::Minutes(n) => format!("每天练习{}分钟", n),
+        }
+    }
+
+    /// 某天的完成比例，超过目标时大于1
+    fn ratio(self, day: &DayRecord) -> f64 {
+        match self {
+            DailyGoal::Answers(n) => day.answers as f64 / n.max(1) as f64,
+            DailyGoal::Minutes(n) => day.seconds as f64 / (n.max(1) * 60) as f64,
+        }
+    }
+}
+
+/// 一天的练习记录
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DayRecord {
+    pub answers: usize, // 作答次数
+    pub correct: usize, // 答对次数
+    pub seconds: u64,   // 练习时长(秒)
+}
+
+/// 单个方案下的字根标记
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SchemeProgress {
+    #[serde(default)]
+    pub known: BTreeSet<String>,             // 已掌握，不再出现
+    #[serde(default)]
+    pub suspended: BTreeMap<String, String>, // 暂停练习 -> 恢复日期，到期自动恢复
+}
+
+/// 字根标记类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mark {
+    Known,     // 已掌握
+    Suspended, // 暂停
+}
+
+impl Mark {
+    pub fn label(self) -> &'static str {
+        match self {
+            Mark::Known => "已掌握",
+            Mark::Suspended => "暂停",
+        }
+    }
+}
+
+/// 方案在档案中的键：优先使用方案标识和版本号，没有元数据时使用字根文件路径
+pub fn scheme_key(radical_file: &str, meta: Option<&SchemeMeta>) -> String {
+    match meta.filter(|m| !m.name.is_empty()) {
+        Some(m) if m.version.is_empty() => m.name.clone(),
+        Some(m) => format!("{} {}", m.name, m.version),
+        None => radical_file.to_string(),
+    }
+}
+
+impl Profile {
+    /// 默认档案名称：当前系统用户名
+    pub fn default_name() -> String {
+        std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .ok()
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "default".to_string())
+    }
+
+    /// 档案文件路径，没有用户数据目录时保存在当前目录的profiles目录下
+    fn path(name: &str) -> PathBuf {
+        // 档案名来自命令行或用户名，不能让它指向profiles目录以外
+        resources::user_data_dir()
+            .unwrap_or_default()
+            .join("profiles")
+            .join(format!("{}.json", resources::safe_file_name(name)))
+    }
+
+    /// 读取档案，文件不存在时返回空档案
+    pub fn load(name: &str) -> Result<Self> {
+        let path = Self::path(name);
+        let mut profile: Self = match fs::read_to_string(&path) {
+            Ok(data) => serde_json::from_str(&data)
+                .with_context(|| format!("档案文件格式错误: {}", path.display()))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Self::default(),
+            Err(e) => {
+                return Err(e).with_context(|| format!("无法读取档案: {}", path.display()))
+            }
+        };
+        profile.name = name.to_string();
+        profile.lift_expired_suspensions(today());
+        Ok(profile)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path(&self.name);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("无法保存档案: {}", path.display()))
+    }
+
+    /// 记录一次作答，每SAVE_INTERVAL次保存一次，latency_ms为反应时间
+    pub fn record_answer(&mut self, correct: bool, latency_ms: Option<u64>) -> Result<()> {
+        let date = date_key(today());
+        let seconds = latency_ms.map_or(0, |ms| (ms / 1000).min(MAX_ANSWER_SECONDS));
+        let day = self.days.entry(date.clone()).or_default();
+        day.answers += 1;
+        if correct {
+            day.correct += 1;
+        }
+        day.seconds += seconds;
+        self.last_answer = Some(AnswerEffect {
+            date,
+            correct,
+            seconds,
+            unlocked: Vec::new(),
+        });
+        self.unsaved += 1;
+        if self.unsaved >= SAVE_INTERVAL {
+            self.unsaved = 0;
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    /// 撤销最近一次作答的记录和这次作答达成的成就
+    pub fn undo_last_answer(&mut self) -> Result<()> {
+        let Some(effect) = self.last_answer.take() else {
+            return Ok(());
+        };
+        // 撤销了达成的成就时立即保存，否则随作答记录一起分批保存
+        let unlocked = !effect.unlocked.is_empty();
+        if let Some(day) = self.days.get_mut(&effect.date) {
+            day.answers = day.answers.saturating_sub(1);
+            if effect.correct {
+                day.correct = day.correct.saturating_sub(1);
+            }
+            day.seconds = day.seconds.saturating_sub(effect.seconds);
+        }
+        for id in &effect.unlocked {
+            self.achievements.remove(id);
+        }
+        if unlocked {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    /// 记录达成的成就，之前未达成时返回true(不保存)
+    pub fn unlock(&mut self, id: &str) -> bool {
+        if self.achievements.contains_key(id) {
+            return false;
+        }
+        self.achievements.insert(id.to_string(), date_key(today()));
+        // 作答中达成的成就在撤销这次作答时一并撤销
+        if let Some(effect) = &mut self.last_answer {
+            effect.unlocked.push(id.to_string());
+        }
+        true
+    }
+
+    fn day(&self, date: NaiveDate) -> Option<&DayRecord> {
+        self.days.get(&date_key(date))
+    }
+
+    fn goal_met(&self, date: NaiveDate) -> bool {
+        self.day(date).is_some_and(|d| self.goal.ratio(d) >= 1.0)
+    }
+
+    /// 连续完成目标的天数；今天还没完成时从昨天算起
+    pub fn streak(&self) -> usize {
+        self.streak_on(today())
+    }
+
+    fn streak_on(&self, today: NaiveDate) -> usize {
+        let mut date = today;
+        if !self.goal_met(date) {
+            date -= Duration::days(1);
+        }
+        let mut streak = 0;
+        while self.goal_met(date) {
+            streak += 1;
+            date -= Duration::days(1);
+        }
+        streak
+    }
+
+    /// 今天的进度，如"今日: 120/200次 (60%)"
+    pub fn today_summary(&self) -> String {
+        self.summary_on(today())
+    }
+
+    fn summary_on(&self, today: NaiveDate) -> String {
+        let day = self.day(today).cloned().unwrap_or_default();
+        let done = match self.goal {
+            DailyGoal::Answers(n) => format!("{}/{}次", day.answers, n),
+            DailyGoal::Minutes(n) => format!("{}/{}分钟", day.seconds / 60, n),
+        };
+        let ratio = self.goal.ratio(&day);
+        let status = if ratio >= 1.0 { "，已完成" } else { "" };
+        format!("今日: {} ({:.0}%{})", done, ratio * 100.0, status)
+    }
+
+    /// 今天的进度和连续打卡天数，显示在欢迎界面和继续存档的确认框中
+    pub fn progress_line(&self) -> String {
+        format!("{} | 连续打卡: {}天", self.today_summary(), self.streak())
+    }
+
+    /// 最近几周的练习热力图，每列一周、每行一个星期几，颜色深浅表示目标完成度
+    pub fn heatmap(&self) -> Vec<Line<'static>> {
+        self.heatmap_on(today())
+    }
+
+    fn heatmap_on(&self, today: NaiveDate) -> Vec<Line<'static>> {
+        // 从若干周前的星期一开始
+        let start = today
+            - Duration::days(today.weekday().num_days_from_monday() as i64)
+            - Duration::weeks(HEATMAP_WEEKS - 1);
+        ["一", "二", "三", "四", "五", "六", "日"]
+            .iter()
+            .enumerate()
+            .map(|(weekday, name)| {
+                let mut spans = vec![Span::raw(format!("{} ", name))];
+                for week in 0..HEATMAP_WEEKS {
+                    let date = start + Duration::days(week * 7 + weekday as i64);
+                    let (cell, color) = if date > today {
+                        ("  ", Color::Reset)
+                    } else {
+                        match self.day(date).map(|d| self.goal.ratio(d)) {
+                            None => ("· ", Color::DarkGray),
+                            Some(r) if r < 0.5 => ("░ ", Color::Green),
+                            Some(r) if r < 1.0 => ("▒ ", Color::Green),
+                            Some(_) => ("█ ", Color::Green),
+                        }
+                    };
+                    spans.push(Span::styled(cell, Style::default().fg(color)));
+                }
+                Line::from(spans)
+            })
+            .collect()
+    }
+
+    /// 设置每日目标，确认后保存
+    pub fn show_goal_settings(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    ) -> Result<()> {
+        let goals = [
+            DailyGoal::Answers(100),
+            DailyGoal::Answers(200),
+            DailyGoal::Answers(300),
+            DailyGoal::Answers(500),
+            DailyGoal::Minutes(10),
+            DailyGoal::Minutes(15),
+            DailyGoal::Minutes(20),
+            DailyGoal::Minutes(30),
+        ];
+        let fields = vec![Field::choice(
+            "每日目标",
+            "达到目标的日子计入连续打卡天数，练习时长按每次作答的反应时间累计(单次最多60秒)",
+            goals.iter().map(|&g| (g, g.label())).collect(),
+            |p: &Self| p.goal,
+            |p: &mut Self, goal| p.goal = goal,
+        )];
+        let previous = self.goal;
+        if SettingsForm::new("每日目标", fields).run(terminal, self)? {
+            self.save()
+        } else {
+            self.goal = previous;
+            Ok(())
+        }
+    }
+
+    /// 方案下所有不参与练习的字根(已掌握和暂停)
+    pub fn excluded(&self, scheme: &str) -> BTreeSet<String> {
+        self.schemes
+            .get(scheme)
+            .map(|p| p.known.iter().chain(p.suspended.keys()).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// 标记字根，同一字根只保留一种标记；暂停的字根在SUSPEND_DAYS天后自动恢复
+    pub fn mark(&mut self, scheme: &str, radical: &str, mark: Mark) {
+        self.mark_on(scheme, radical, mark, today());
+    }
+
+    fn mark_on(&mut self, scheme: &str, radical: &str, mark: Mark, today: NaiveDate) {
+        let progress = self.schemes.entry(scheme.to_string()).or_default();
+        progress.known.remove(radical);
+        progress.suspended.remove(radical);
+        match mark {
+            Mark::Known => {
+                progress.known.insert(radical.to_string());
+            }
+            Mark::Suspended => {
+                let until = date_key(today + Duration::days(SUSPEND_DAYS));
+                progress.suspended.insert(radical.to_string(), until);
+            }
+        }
+    }
+
+    /// 恢复已到期的暂停字根(读取档案时调用，随下次保存写入)
+    fn lift_expired_suspensions(&mut self, today: NaiveDate) {
+        let today = date_key(today);
+        for progress in self.schemes.values_mut() {
+            progress.suspended.retain(|_, until| *until > today);
+        }
+        self.schemes
+            .retain(|_, p| !p.known.is_empty() || !p.suspended.is_empty());
+    }
+
+    /// 取消字根的标记，使其重新参与练习
+    fn unmark(&mut self, scheme: &str, radical: &str) {
+        if let Some(progress) = self.schemes.get_mut(scheme) {
+            progress.known.remove(radical);
+            progress.suspended.remove(radical);
+            if progress.known.is_empty() && progress.suspended.is_empty() {
+                self.schemes.remove(scheme);
+            }
+        }
+    }
+
+    /// 所有标记，按方案、标记类型排列，暂停的字根附带恢复日期
+    fn entries(&self) -> Vec<(String, String, Mark, Option<String>)> {
+        self.schemes
+            .iter()
+            .flat_map(|(scheme, p)| {
+                let suspended = p
+                    .suspended
+                    .iter()
+                    .map(move |(r, until)| (scheme, r, Mark::Suspended, Some(until)));
+                let known = p.known.iter().map(move |r| (scheme, r, Mark::Known, None));
+                suspended.chain(known)
+            })
+            .map(|(scheme, radical, mark, until)| {
+                (scheme.clone(), radical.clone(), mark, until.cloned())
+            })
+            .collect()
+    }
+
+    /// 管理已掌握和暂停的字根，Enter恢复选中的字根，修改立即保存
+    pub fn show_manager(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    ) -> Result<()> {
+        let mut selected = 0;
+        loop {
+            let entries = self.entries();
+            selected = selected.min(entries.len().saturating_sub(1));
+            terminal.draw(|f| {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .margin(1)
+                    .constraints([
+                        Constraint::Length(3),
+                        Constraint::Min(5),
+                        Constraint::Length(3),
+                    ])
+                    .split(f.area());
+
+                let title = Paragraph::new(format!("字根标记管理 (档案: {})", self.name))
+                    .block(Block::default().borders(Borders::ALL))
+                    .alignment(Alignment::Center);
+                f.render_widget(title, chunks[0]);
+
+                if entries.is_empty() {
+                    let empty = Paragraph::new("没有已掌握或暂停的字根")
+                        .block(Block::default().borders(Borders::ALL))
+                        .alignment(Alignment::Center);
+                    f.render_widget(empty, chunks[1]);
+                } else {
+                    let items: Vec<ListItem> = entries
+                        .iter()
+                        .map(|(scheme, radical, mark, until)| {
+                            let color = match mark {
+                                Mark::Known => Color::Green,
+                                Mark::Suspended => Color::Yellow,
+                            };
+                            let until = until
+                                .as_ref()
+                                .map_or(String::new(), |d| format!("  {}恢复", d));
+                            ListItem::new(format!(
+                                "[{}] {}  ({}){}",
+                                mark.label(),
+                                radical,
+                                scheme,
+                                until
+                            ))
+                            .style(Style::default().fg(color))
+                        })
+                        .collect();
+                    let list = List::new(items)
+                        .block(Block::default().borders(Borders::ALL))
+                        .highlight_style(Style::default().bg(Color::Blue).fg(Color::White))
+                        .highlight_symbol(">> ");
+                    let mut state = ListState::default().with_selected(Some(selected));
+                    f.render_stateful_widget(list, chunks[1], &mut state);
+                }
+
+                let help = Paragraph::new("↑/↓: 选择 | Enter/Delete: 恢复练习 | ESC: 返回")
+                    .block(Block::default().borders(Borders::ALL))
+                    .alignment(Alignment::Center);
+                f.render_widget(help, chunks[2]);
+            })?;
+
+            if let Event::Key(key) = event::read()? {
+                #[cfg(windows)]
+                if key.kind != event::KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Up => selected = selected.saturating_sub(1),
+                    KeyCode::Down => selected += 1,
+                    KeyCode::Enter | KeyCode::Delete => {
+                        if let Some((scheme, radical, ..)) = entries.get(selected) {
+                            self.unmark(scheme, radical);
+                            self.save()?;
+                        }
+                    }
+                    KeyCode::Esc => return Ok(()),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+fn date_key(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+    }
+
+    fn meta(name: &str, version: &str) -> SchemeMeta {
+        SchemeMeta {
+            name: name.to_string(),
+            version: version.to_string(),
+            ..SchemeMeta::default()
+        }
+    }
+
+    #[test]
+    fn scheme_key_prefers_metadata() {
+        let file = "res/yustar-3.8.0.txt";
+        assert_eq!(
+            scheme_key(file, Some(&meta("yustar", "3.8.0"))),
+            "yustar 3.8.0"
+        );
+        assert_eq!(scheme_key(file, Some(&meta("yustar", ""))), "yustar");
+        assert_eq!(scheme_key(file, Some(&meta("", "3.8.0"))), file);
+        assert_eq!(scheme_key(file, None), file);
+    }
+
+    #[test]
+    fn suspensions_expire_after_suspend_days() {
+        let mut profile = Profile::default();
+        let start = date("2024-02-26");
+        profile.mark_on("yustar", "口", Mark::Suspended, start);
+        profile.mark_on("yustar", "木", Mark::Known, start);
+        assert_eq!(profile.schemes["yustar"].suspended["口"], "2024-03-04");
+
+        // 恢复日期之前仍然暂停
+        profile.lift_expired_suspensions(start + Duration::days(SUSPEND_DAYS - 1));
+        assert_eq!(profile.excluded("yustar").len(), 2);
+
+        profile.lift_expired_suspensions(start + Duration::days(SUSPEND_DAYS));
+        assert_eq!(
+            profile.excluded("yustar"),
+            BTreeSet::from(["木".to_string()])
+        );
+
+        // 没有标记的方案被移除
+        profile.unmark("yustar", "木");
+        profile.lift_expired_suspensions(start);
+        assert!(profile.schemes.is_empty());
+    }
+
+    #[test]
+    fn marks_replace_each_other_and_round_trip() {
+        let mut profile = Profile::default();
+        let day = date("2024-12-30");
+        profile.mark_on("yustar 3.8.0", "口", Mark::Known, day);
+        profile.mark_on("yustar 3.8.0", "口", Mark::Suspended, day);
+        profile.mark_on("yustar 3.8.0", "木", Mark::Known, day);
+        profile.mark_on("yujoy", "口", Mark::Known, day);
+
+        let json = serde_json::to_string(&profile).unwrap();
+        let loaded: Profile = serde_json::from_str(&json).unwrap();
+        let progress = &loaded.schemes["yustar 3.8.0"];
+        assert_eq!(progress.known, BTreeSet::from(["木".to_string()]));
+        assert_eq!(progress.suspended["口"], "2025-01-06");
+        assert_eq!(loaded.excluded("yujoy"), BTreeSet::from(["口".to_string()]));
+        assert!(loaded.excluded("yulight").is_empty());
+    }
+
+    #[test]
+    fn profile_names_stay_inside_profiles_dir() {
+        let path = Profile::path("../../etc/passwd");
+        assert_eq!(path.file_name().unwrap(), ".._.._etc_passwd.json");
+        assert!(path.parent().unwrap().ends_with("profiles"));
+        assert!(Profile::path("张三").ends_with("profiles/张三.json"));
+    }
+
+    /// 在date这天记录answers次作答
+    fn practiced(profile: &mut Profile, date: &str, answers: usize) {
+        let day = DayRecord {
+            answers,
+            correct: answers,
+            seconds: 0,
+        };
+        profile.days.insert(date.to_string(), day);
+    }
+
+    #[test]
+    fn streak_counts_consecutive_goal_days() {
+        let mut profile = Profile {
+            goal: DailyGoal::Answers(200),
+            ..Profile::default()
+        };
+        let today = date("2024-03-01");
+        assert_eq!(profile.streak_on(today), 0);
+
+        practiced(&mut profile, "2024-02-29", 200);
+        practiced(&mut profile, "2024-02-28", 350);
+        practiced(&mut profile, "2024-02-27", 199);
+        practiced(&mut profile, "2024-02-26", 200);
+        // 今天还没完成时从昨天算起，没达到目标的一天中断连续
+        assert_eq!(profile.streak_on(today), 2);
+        practiced(&mut profile, "2024-03-01", 120);
+        assert_eq!(profile.streak_on(today), 2);
+        assert_eq!(profile.summary_on(today), "今日: 120/200次 (60%)");
+
+        practiced(&mut profile, "2024-03-01", 200);
+        assert_eq!(profile.streak_on(today), 3);
+        assert_eq!(profile.summary_on(today), "今日: 200/200次 (100%，已完成)");
+
+        // 昨天没有练习时中断
+        assert_eq!(profile.streak_on(date("2024-03-03")), 0);
+    }
+
+    #[test]
+    fn minutes_goal_uses_practice_time() {
+        let mut profile = Profile {
+            goal: DailyGoal::Minutes(10),
+            ..Profile::default()
+        };
+        let day = DayRecord {
+            answers: 5,
+            correct: 5,
+            seconds: 599,
+        };
+        profile.days.insert("2024-03-01".to_string(), day);
+        assert_eq!(profile.streak_on(date("2024-03-01")), 0);
+        assert_eq!(
+            profile.summary_on(date("2024-03-01")),
+            "今日: 9/10分钟 (100%)"
+        );
+        profile.days.get_mut("2024-03-01").unwrap().seconds = 600;
+        assert_eq!(profile.streak_on(date("2024-03-01")), 1);
+    }
+
+    #[test]
+    fn heatmap_cells_follow_goal_ratio() {
+        let mut profile = Profile {
+            goal: DailyGoal::Answers(100),
+            ..Profile::default()
+        };
+        // 2024-03-06是星期三，最后一列是这一周
+        let today = date("2024-03-06");
+        practiced(&mut profile, "2024-03-04", 49);
+        practiced(&mut profile, "2024-03-05", 50);
+        practiced(&mut profile, "2024-03-06", 100);
+        let rows = profile.heatmap_on(today);
+        assert_eq!(rows.len(), 7);
+        let last_cell = |row: usize| {
+            let spans = &rows[row].spans;
+            assert_eq!(spans.len(), 1 + HEATMAP_WEEKS as usize);
+            spans[spans.len() - 1].content.to_string()
+        };
+        assert_eq!(last_cell(0), "░ ");
+        assert_eq!(last_cell(1), "▒ ");
+        assert_eq!(last_cell(2), "█ ");
+        // 今天之后的日子留空
+        assert_eq!(last_cell(3), "  ");
+        // 没有记录的日子
+        assert_eq!(rows[0].spans[HEATMAP_WEEKS as usize - 1].content, "· ");
+    }
+
+    #[test]
+    fn undo_reverts_daily_record() {
+        let mut profile = Profile::default();
+        profile.record_answer(true, Some(3_500)).unwrap();
+        profile.record_answer(false, Some(120_000)).unwrap();
+        let today = date_key(today());
+        assert_eq!(profile.days[&today].seconds, 3 + MAX_ANSWER_SECONDS);
+
+        profile.undo_last_answer().unwrap();
+        let day = &profile.days[&today];
+        assert_eq!((day.answers, day.correct, day.seconds), (1, 1, 3));
+
+        // 只能撤销最近一次作答
+        profile.undo_last_answer().unwrap();
+        let day = &profile.days[&today];
+        assert_eq!((day.answers, day.correct, day.seconds), (1, 1, 3));
+    }
+}
@@ -0,0 +1,215 @@
+use crate::scheme::SchemeMeta;
+use anyhow::{anyhow, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 编译进程序的默认资源文件：(相对路径, 内容)
+const BUILTIN: &[(&str, &str)] = &[
+    ("res/yujoy-3.8.0.txt", include_str!("../res/yujoy-3.8.0.txt")),
+    ("res/yulight-3.8.0.txt", include_str!("../res/yulight-3.8.0.txt")),
+    ("res/yustar-3.8.0.txt", include_str!("../res/yustar-3.8.0.txt")),
+    ("res/yujoy-3.6.0.txt", include_str!("../res/yujoy-3.6.0.txt")),
+    (
+        "res/yusm-3.9.0-20250522.txt",
+        include_str!("../res/yusm-3.9.0-20250522.txt"),
+    ),
+    ("res/counts.txt", include_str!("../res/counts.txt")),
+    ("res/counts-3.6.0.txt", include_str!("../res/counts-3.6.0.txt")),
+    ("res/counts-sunmoon.txt", include_str!("../res/counts-sunmoon.txt")),
+];
+
+/// 资源文件的来源
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    Disk(PathBuf), // 磁盘上的文件(优先)
+    Builtin,       // 内置资源
+}
+
+/// 资源文件的类型，按内容判断
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Radicals, // 字根编码文件(每行"编码 字根")
+    Counts,   // 字根频率文件(每行"字根 次数")
+}
+
+/// 可用的资源文件
+#[derive(Debug, Clone)]
+pub struct ResourceEntry {
+    pub path: String,             // 设置中使用的路径，如 res/counts.txt
+    pub source: Source,           // 实际来源
+    pub kind: Kind,               // 文件类型
+    pub meta: Option<SchemeMeta>, // 文件开头的方案元数据
+}
+
+/// 按第一行数据判断文件类型，不像字根或频率文件时返回None
+pub fn classify(content: &str) -> Option<Kind> {
+    let line = content
+        .lines()
+        .find(|l| !l.starts_with('#') && !l.trim().is_empty())?;
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    if parts.len() < 2 {
+        return None;
+    }
+    if parts[1].parse::<u64>().is_ok() {
+        Some(Kind::Counts)
+    } else if parts[0].chars().all(|c| c.is_ascii_alphabetic()) {
+        Some(Kind::Radicals)
+    } else {
+        None
+    }
+}
+
+/// 用户数据目录，存放转换生成的方案文件
+pub fn user_data_dir() -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        std::env::var_os("HOME").map(|h| PathBuf::from(h).join("Library/Application Support"))
+    } else {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local/share")))
+    };
+    base.map(|b| b.join("yu-practice-game"))
+}
+
+/// 用户数据目录中的文件路径，转换等命令的默认输出位置；无法确定用户数据目录时使用当前目录
+pub fn user_data_path(file_name: &str) -> String {
+    user_data_dir()
+        .map_or_else(|| PathBuf::from(file_name), |dir| dir.join(file_name))
+        .to_string_lossy()
+        .to_string()
+}
+
+/// 把档案名、方案键等转换成可用作文件名的形式，路径分隔符等字符替换为下划线
+pub fn safe_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// 查找资源文件的磁盘位置：依次尝试可执行文件目录和当前目录
+fn find_on_disk(path: &str) -> Option<PathBuf> {
+    let path = Path::new(path);
+    if path.is_absolute() {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+    if let Some(exe_dir) = exe_dir() {
+        let exe_path = exe_dir.join(path);
+        if exe_path.is_file() {
+            return Some(exe_path);
+        }
+    }
+    path.is_file().then(|| path.to_path_buf())
+}
+
+fn exe_dir() -> Option<PathBuf> {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+}
+
+fn builtin(path: &str) -> Option<&'static str> {
+    let normalized = path.trim_start_matches("./").replace('\\', "/");
+    BUILTIN
+        .iter()
+        .find(|(name, _)| *name == normalized)
+        .map(|(_, content)| *content)
+}
+
+/// 读取资源文件内容，磁盘上的文件优先，找不到时使用内置资源
+pub fn read(path: &str) -> Result<String> {
+    if let Some(disk_path) = find_on_disk(path) {
+        return Ok(fs::read_to_string(disk_path)?);
+    }
+    builtin(path)
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("文件不存在: {}", path))
+}
+
+/// 查找资源文件的目录：(目录, 是否为res目录)，依次为可执行文件旁和当前目录下的res目录、用户数据目录
+fn search_dirs() -> Vec<(PathBuf, bool)> {
+    let mut dirs: Vec<(PathBuf, bool)> = Vec::new();
+    if let Some(exe_dir) = exe_dir() {
+        dirs.push((exe_dir.join("res"), true));
+    }
+    dirs.push((PathBuf::from("res"), true));
+    if let Some(data_dir) = user_data_dir() {
+        dirs.push((data_dir, false));
+    }
+    dirs
+}
+
+/// res目录和用户数据目录中文件名包含chaifen的拆分表，用于给没有例字的字根文件补充例字
+pub fn chaifen_files() -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = search_dirs()
+        .into_iter()
+        .filter_map(|(dir, _)| fs::read_dir(dir).ok())
+        .flat_map(|read_dir| read_dir.flatten().map(|e| e.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .is_some_and(|n| n.to_string_lossy().contains("chaifen"))
+        })
+        .collect();
+    files.dedup();
+    files
+}
+
+/// 列出所有可用的资源文件，包括内置的、res目录中的和用户数据目录中的
+pub fn list() -> Vec<ResourceEntry> {
+    let mut entries: BTreeMap<String, Source> = BTreeMap::new();
+    for (name, _) in BUILTIN {
+        entries.insert(name.to_string(), Source::Builtin);
+    }
+
+    // 磁盘上的同名文件覆盖内置资源；res目录中的文件用相对路径表示
+    for (dir, relative) in search_dirs() {
+        let Ok(read_dir) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in read_dir.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if !file_name.ends_with(".txt") {
+                continue;
+            }
+            let path = if relative {
+                format!("res/{}", file_name)
+            } else {
+                entry.path().to_string_lossy().to_string()
+            };
+            entries
+                .entry(path)
+                .and_modify(|s| {
+                    if *s == Source::Builtin {
+                        *s = Source::Disk(entry.path());
+                    }
+                })
+                .or_insert_with(|| Source::Disk(entry.path()));
+        }
+    }
+
+    entries
+        .into_iter()
+        .filter_map(|(path, source)| {
+            let content = match &source {
+                Source::Disk(disk_path) => fs::read_to_string(disk_path).ok()?,
+                Source::Builtin => builtin(&path)?.to_string(),
+            };
+            Some(ResourceEntry {
+                kind: classify(&content)?,
+                meta: SchemeMeta::from_header_comments(&content),
+                path,
+                source,
+            })
+        })
+        .collect()
+}
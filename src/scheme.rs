@@ -0,0 +1,412 @@
+use crate::resources::{self, Kind, ResourceEntry};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+
+/// 宇浩方案系列：(标识关键字, 中文名)
+const FAMILIES: &[(&[&str], &str)] = &[
+    (&["yusm", "sunmoon", "日月"], "日月"),
+    (&["yustar", "星陈"], "星陈"),
+    (&["yulight", "光华"], "光华"),
+    (&["yujoy", "卿云"], "卿云"),
+];
+
+/// 方案元数据，来自拆分表的YAML文件头，并以注释形式保存在导出的文件开头
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SchemeMeta {
+    pub name: String,    // 方案标识(YAML中的name)
+    pub version: String, // 版本号
+    pub family: String,  // 方案系列，如卿云、日月，未知时为空
+}
+
+impl SchemeMeta {
+    /// 从拆分表"..."之前的文件头解析元数据
+    ///
+    /// 读取顶层的`name`和`version`字段；方案系列依次从name、注释行和文件名中识别
+    pub fn from_yaml_header(lines: &[String], file_name: &str) -> Self {
+        let mut meta = Self::default();
+        let mut comments = String::new();
+        for line in lines {
+            if let Some(comment) = line.trim_start().strip_prefix('#') {
+                comments.push_str(comment);
+                comments.push('\n');
+                continue;
+            }
+            // 只取顶层字段，忽略缩进的子项
+            if line.starts_with(char::is_whitespace) {
+                continue;
+            }
+            if let Some((key, value)) = line.split_once(':') {
+                let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+                match key.trim() {
+                    "name" => meta.name = value.to_string(),
+                    "version" => meta.version = value.to_string(),
+                    _ => {}
+                }
+            }
+        }
+        meta.family = detect_family(&meta.name)
+            .or_else(|| detect_family(&comments))
+            .or_else(|| detect_family(file_name))
+            .unwrap_or_default()
+            .to_string();
+        meta
+    }
+
+    /// 读取导出文件开头"# key: value"形式的元数据，没有元数据时返回None
+    pub fn read_from_file(path: &str) -> Option<Self> {
+        let content = resources::read(path).ok()?;
+        Self::from_header_comments(&content)
+    }
+
+    /// 从文件内容开头的注释行解析元数据
+    pub fn from_header_comments(content: &str) -> Option<Self> {
+        let mut meta = Self::default();
+        let mut found = false;
+        for line in content.lines() {
+            let Some(comment) = line.strip_prefix('#') else {
+                break;
+            };
+            if let Some((key, value)) = comment.split_once(':') {
+                let value = value.trim().to_string();
+                match key.trim() {
+                    "name" => meta.name = value,
+                    "version" => meta.version = value,
+                    "family" => meta.family = value,
+                    _ => continue,
+                }
+                found = true;
+            }
+        }
+        found.then_some(meta)
+    }
+
+    /// 以注释形式写入文件开头
+    pub fn write_header(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(out, "# name: {}", self.name)?;
+        writeln!(out, "# version: {}", self.version)?;
+        if !self.family.is_empty() {
+            writeln!(out, "# family: {}", self.family)?;
+        }
+        Ok(())
+    }
+
+    /// 用于界面显示的名称，如"宇浩·卿云 3.8.0"
+    pub fn display_name(&self) -> String {
+        let name = if self.family.is_empty() {
+            self.name.clone()
+        } else {
+            format!("宇浩·{}", self.family)
+        };
+        if self.version.is_empty() {
+            name
+        } else {
+            format!("{} {}", name, self.version)
+        }
+    }
+
+    /// 是否为日月方案(编码不截断为两码)
+    pub fn is_sun_moon(&self) -> bool {
+        self.family == "日月"
+    }
+}
+
+fn detect_family(text: &str) -> Option<&'static str> {
+    let text = text.to_lowercase();
+    FAMILIES
+        .iter()
+        .find(|(keys, _)| keys.iter().any(|k| text.contains(k)))
+        .map(|(_, family)| *family)
+}
+
+/// 一套可练习的方案：字根文件和与之配对的频率文件
+#[derive(Debug, Clone)]
+pub struct Scheme {
+    pub label: String,          // 显示名称
+    pub radical_file: String,   // 字根编码文件
+    pub frequency_file: String, // 频率文件
+}
+
+/// 扫描可用的资源文件，为每个字根文件自动配对频率文件
+pub fn discover_schemes(entries: &[ResourceEntry]) -> Vec<Scheme> {
+    let counts: Vec<&ResourceEntry> = entries.iter().filter(|e| e.kind == Kind::Counts).collect();
+    let mut schemes: Vec<(Scheme, String)> = entries
+        .iter()
+        .filter(|e| e.kind == Kind::Radicals)
+        .map(|radical| {
+            // 取得分最高的频率文件，同分时保持文件名顺序
+            let frequency_file = counts
+                .iter()
+                .max_by_key(|c| (pairing_score(radical, c), std::cmp::Reverse(&c.path)))
+                .map_or_else(|| "res/counts.txt".to_string(), |c| c.path.clone());
+            let scheme = Scheme {
+                label: radical
+                    .meta
+                    .as_ref()
+                    .map_or_else(|| file_stem(&radical.path), SchemeMeta::display_name),
+                radical_file: radical.path.clone(),
+                frequency_file,
+            };
+            (scheme, origin(radical))
+        })
+        .collect();
+
+    // 同名方案附上来源以便区分，来源也相同时再附上文件名
+    let labels: Vec<(String, String)> = schemes
+        .iter()
+        .map(|(s, origin)| (s.label.clone(), origin.clone()))
+        .collect();
+    for (scheme, origin) in &mut schemes {
+        let same_label = labels.iter().filter(|(l, _)| *l == scheme.label);
+        if same_label.clone().count() > 1 {
+            let suffix = if same_label.filter(|(_, o)| o == origin).count() > 1 {
+                format!("{} {}", origin, file_stem(&scheme.radical_file))
+            } else {
+                origin.clone()
+            };
+            scheme.label = format!("{} [{}]", scheme.label, suffix);
+        }
+    }
+    schemes.into_iter().map(|(scheme, _)| scheme).collect()
+}
+
+/// 资源文件的来源：内置、res目录、用户数据目录，其他位置显示所在目录
+fn origin(entry: &ResourceEntry) -> String {
+    let path = std::path::Path::new(&entry.path);
+    if entry.source == resources::Source::Builtin {
+        return "内置".to_string();
+    }
+    if path.starts_with("res") {
+        return "res".to_string();
+    }
+    if resources::user_data_dir().is_some_and(|dir| path.starts_with(dir)) {
+        return "用户数据目录".to_string();
+    }
+    path.parent()
+        .map_or_else(String::new, |dir| dir.display().to_string())
+}
+
+/// 字根文件与频率文件的匹配程度
+///
+/// 同一目录下的文件优先，其次看方案系列、文件名后缀(如yucode-custom与counts-custom)
+/// 和版本号；频率文件标明了其他系列时不配对
+fn pairing_score(radical: &ResourceEntry, counts: &ResourceEntry) -> i32 {
+    let mut score = 0;
+    let dir = |p: &str| std::path::Path::new(p).parent().map(|d| d.to_path_buf());
+    if dir(&radical.path) == dir(&counts.path) {
+        score += 5;
+    }
+    if let (Some(r), Some(c)) = (&radical.meta, &counts.meta) {
+        if !c.family.is_empty() {
+            score += if c.family == r.family { 4 } else { -5 };
+        }
+        if !r.version.is_empty() && r.version == c.version {
+            score += 2;
+        }
+    }
+    let radical_stem = file_stem(&radical.path);
+    let counts_stem = file_stem(&counts.path);
+    let radical_suffix = radical_stem.split_once('-').map(|(_, s)| s);
+    let counts_suffix = counts_stem.split_once('-').map(|(_, s)| s);
+    if radical_suffix.is_some() && radical_suffix == counts_suffix {
+        score += 3;
+    }
+    // 其他条件都不满足时优先使用通用的counts.txt
+    if counts_stem == "counts" {
+        score += 1;
+    }
+    score
+}
+
+fn file_stem(path: &str) -> String {
+    std::path::Path::new(path)
+        .file_stem()
+        .map_or_else(|| path.to_string(), |s| s.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn yaml_header_top_level_fields() {
+        let header = lines(
+            "---\nname: \"yustar_chaifen\"\nversion: '3.8.0'\nsort: by_weight\ncolumns:\n  name: text\n  version: x\n",
+        );
+        let meta = SchemeMeta::from_yaml_header(&header, "chaifen.csv");
+        assert_eq!(meta.name, "yustar_chaifen");
+        assert_eq!(meta.version, "3.8.0");
+        assert_eq!(meta.family, "星陈");
+        assert_eq!(meta.display_name(), "宇浩·星陈 3.8.0");
+        assert!(!meta.is_sun_moon());
+    }
+
+    #[test]
+    fn family_from_comments_or_file_name() {
+        let header = lines("# 宇浩日月拆分表\nname: chaifen\nversion: 3.9.0");
+        let meta = SchemeMeta::from_yaml_header(&header, "chaifen.csv");
+        assert_eq!(meta.family, "日月");
+        assert!(meta.is_sun_moon());
+
+        let header = lines("name: chaifen");
+        let meta = SchemeMeta::from_yaml_header(&header, "yujoy_chaifen.txt");
+        assert_eq!(meta.family, "卿云");
+
+        let meta = SchemeMeta::from_yaml_header(&header, "other.txt");
+        assert_eq!(meta.family, "");
+        assert_eq!(meta.display_name(), "chaifen");
+    }
+
+    #[test]
+    fn header_comments_round_trip() {
+        let meta = SchemeMeta {
+            name: "yujoy".to_string(),
+            version: "3.8.0".to_string(),
+            family: "卿云".to_string(),
+        };
+        let mut out = Vec::new();
+        meta.write_header(&mut out).unwrap();
+        let content = format!(
+            "{}jd 口\n# name: 不在文件开头\n",
+            String::from_utf8(out).unwrap()
+        );
+        assert_eq!(SchemeMeta::from_header_comments(&content), Some(meta));
+    }
+
+    #[test]
+    fn header_comments_missing() {
+        assert_eq!(SchemeMeta::from_header_comments("jd 口\n"), None);
+        assert_eq!(
+            SchemeMeta::from_header_comments("# 普通注释\njd 口\n"),
+            None
+        );
+    }
+
+    fn entry(path: &str, kind: Kind, meta: Option<(&str, &str)>) -> ResourceEntry {
+        ResourceEntry {
+            path: path.to_string(),
+            source: if path.starts_with("res/") {
+                resources::Source::Builtin
+            } else {
+                resources::Source::Disk(path.into())
+            },
+            kind,
+            meta: meta.map(|(family, version)| SchemeMeta {
+                name: String::new(),
+                version: version.to_string(),
+                family: family.to_string(),
+            }),
+        }
+    }
+
+    #[test]
+    fn pairing_prefers_matching_family_and_version() {
+        let radical = entry(
+            "res/yujoy-3.8.0.txt",
+            Kind::Radicals,
+            Some(("卿云", "3.8.0")),
+        );
+        let generic = entry("res/counts.txt", Kind::Counts, None);
+        let same = entry("res/counts-x.txt", Kind::Counts, Some(("卿云", "3.8.0")));
+        let other = entry("res/counts-sunmoon.txt", Kind::Counts, Some(("日月", "")));
+        let elsewhere = entry("data/counts.txt", Kind::Counts, None);
+        assert_eq!(pairing_score(&radical, &generic), 6);
+        assert_eq!(pairing_score(&radical, &same), 11);
+        assert_eq!(pairing_score(&radical, &other), 0);
+        assert_eq!(pairing_score(&radical, &elsewhere), 1);
+    }
+
+    #[test]
+    fn pairing_matches_file_name_suffix() {
+        let radical = entry("out/yucode-custom.txt", Kind::Radicals, None);
+        let custom = entry("out/counts-custom.txt", Kind::Counts, None);
+        let generic = entry("out/counts.txt", Kind::Counts, None);
+        assert!(pairing_score(&radical, &custom) > pairing_score(&radical, &generic));
+    }
+
+    #[test]
+    fn discover_pairs_and_labels_schemes() {
+        let entries = vec![
+            entry("res/counts-sunmoon.txt", Kind::Counts, Some(("日月", ""))),
+            entry("res/counts.txt", Kind::Counts, None),
+            entry(
+                "res/yujoy-3.8.0.txt",
+                Kind::Radicals,
+                Some(("卿云", "3.8.0")),
+            ),
+            entry(
+                "res/yusm-3.9.0.txt",
+                Kind::Radicals,
+                Some(("日月", "3.9.0")),
+            ),
+            entry("res/mine.txt", Kind::Radicals, None),
+            entry(
+                "user/yujoy-3.8.0.txt",
+                Kind::Radicals,
+                Some(("卿云", "3.8.0")),
+            ),
+        ];
+        let schemes: Vec<(String, String)> = discover_schemes(&entries)
+            .into_iter()
+            .map(|s| (s.label, s.frequency_file))
+            .collect();
+        assert_eq!(
+            schemes,
+            [
+                (
+                    "宇浩·卿云 3.8.0 [内置]".to_string(),
+                    "res/counts.txt".to_string()
+                ),
+                (
+                    "宇浩·日月 3.9.0".to_string(),
+                    "res/counts-sunmoon.txt".to_string()
+                ),
+                ("mine".to_string(), "res/counts.txt".to_string()),
+                (
+                    "宇浩·卿云 3.8.0 [user]".to_string(),
+                    "res/counts.txt".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn discover_without_counts_uses_default() {
+        let entries = vec![entry("res/mine.txt", Kind::Radicals, None)];
+        let schemes = discover_schemes(&entries);
+        assert_eq!(schemes[0].frequency_file, "res/counts.txt");
+    }
+
+    #[test]
+    fn duplicate_labels_name_their_source() {
+        let meta = Some(("卿云", "3.8.0"));
+        let data_dir = resources::user_data_dir().unwrap_or_default();
+        let in_data_dir = data_dir.join("yujoy-3.8.0.txt");
+        let mut res_copy = entry("res/yujoy-3.8.0.txt", Kind::Radicals, meta);
+        res_copy.source = resources::Source::Disk("/opt/game/res/yujoy-3.8.0.txt".into());
+        let entries = vec![
+            entry("res/yujoy-3.8.0.txt", Kind::Radicals, meta),
+            res_copy,
+            entry(in_data_dir.to_str().unwrap(), Kind::Radicals, meta),
+            entry("/tmp/a/yujoy.txt", Kind::Radicals, meta),
+            entry("/tmp/a/yujoy-copy.txt", Kind::Radicals, meta),
+        ];
+        let labels: Vec<String> = discover_schemes(&entries)
+            .into_iter()
+            .map(|s| s.label)
+            .collect();
+        assert_eq!(
+            labels,
+            [
+                "宇浩·卿云 3.8.0 [内置]",
+                "宇浩·卿云 3.8.0 [res]",
+                "宇浩·卿云 3.8.0 [用户数据目录]",
+                "宇浩·卿云 3.8.0 [/tmp/a yujoy]",
+                "宇浩·卿云 3.8.0 [/tmp/a yujoy-copy]",
+            ]
+        );
+    }
+}
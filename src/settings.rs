@@ -0,0 +1,356 @@
+use crate::file_picker::{FilePicker, Validation};
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Style},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Terminal,
+};
+use std::io;
+
+/// 手动选择文件的占位项，放在路径候选列表的末尾
+const MANUAL_ENTRY: &str = "按右方向键手动输入→";
+
+type Getter<'a, C, T> = Box<dyn Fn(&C) -> T + 'a>;
+type Setter<'a, C, T> = Box<dyn Fn(&mut C, T) + 'a>;
+type Formatter<'a, C> = Box<dyn Fn(&C, f64) -> String + 'a>;
+
+/// 数值范围调整一步，结果对齐到步长网格上，避免浮点误差累积
+fn step_range(value: f64, forward: bool, (min, max, step): (f64, f64, f64)) -> f64 {
+    let value = if forward { value + step } else { value - step };
+    let value = ((value - min) / step).round() * step + min;
+    value.clamp(min, max)
+}
+
+/// 设置项的类型
+enum FieldKind<'a, C> {
+    /// 在若干选项中切换，到两端停止
+    Enum {
+        options: Vec<String>,
+        get: Getter<'a, C, usize>,
+        set: Setter<'a, C, usize>,
+    },
+    /// 数值范围，按固定步长调整
+    Range {
+        min: f64,
+        max: f64,
+        step: f64,
+        get: Getter<'a, C, f64>,
+        set: Setter<'a, C, f64>,
+        format: Formatter<'a, C>,
+    },
+    /// 文件路径：在候选文件中切换，末尾的占位项按右方向键打开文件浏览器
+    Path {
+        candidates: Vec<String>,
+        validation: Validation,
+        get: Getter<'a, C, String>,
+        set: Setter<'a, C, String>,
+        format: Box<dyn Fn(&str) -> String + 'a>,
+    },
+    /// 开关：←关闭，→开启
+    Bool {
+        on: &'static str,
+        off: &'static str,
+        get: Getter<'a, C, bool>,
+        set: Setter<'a, C, bool>,
+    },
+}
+
+/// 设置表单中的一项
+pub struct Field<'a, C> {
+    label: &'static str, // 选项名称
+    help: String,        // 选中时在底部显示的说明
+    kind: FieldKind<'a, C>,
+}
+
+impl<'a, C> Field<'a, C> {
+    /// 枚举选项，options为(取值, 显示文字)
+    pub fn choice<T, L>(
+        label: &'static str,
+        help: impl Into<String>,
+        options: Vec<(T, L)>,
+        get: impl Fn(&C) -> T + 'a,
+        set: impl Fn(&mut C, T) + 'a,
+    ) -> Self
+    where
+        T: Copy + PartialEq + 'a,
+        L: Into<String>,
+    {
+        let (values, labels): (Vec<T>, Vec<L>) = options.into_iter().unzip();
+        let lookup = values.clone();
+        Self {
+            label,
+            help: help.into(),
+            kind: FieldKind::Enum {
+                options: labels.into_iter().map(Into::into).collect(),
+                get: Box::new(move |c| {
+                    let value = get(c);
+                    lookup.iter().position(|v| *v == value).unwrap_or(0)
+                }),
+                set: Box::new(move |c, i| set(c, values[i])),
+            },
+        }
+    }
+
+    /// 数值范围选项，bounds为(最小值, 最大值, 步长)
+    pub fn range(
+        label: &'static str,
+        help: impl Into<String>,
+        (min, max, step): (f64, f64, f64),
+        get: impl Fn(&C) -> f64 + 'a,
+        set: impl Fn(&mut C, f64) + 'a,
+        format: impl Fn(&C, f64) -> String + 'a,
+    ) -> Self {
+        Self {
+            label,
+            help: help.into(),
+            kind: FieldKind::Range {
+                min,
+                max,
+                step,
+                get: Box::new(get),
+                set: Box::new(set),
+                format: Box::new(format),
+            },
+        }
+    }
+
+    /// 文件路径选项，format把路径转换为显示文字
+    pub fn path(
+        label: &'static str,
+        help: impl Into<String>,
+        candidates: Vec<String>,
+        validation: Validation,
+        get: impl Fn(&C) -> String + 'a,
+        set: impl Fn(&mut C, String) + 'a,
+        format: impl Fn(&str) -> String + 'a,
+    ) -> Self {
+        Self {
+            label,
+            help: help.into(),
+            kind: FieldKind::Path {
+                candidates: candidates.into_iter().chain([MANUAL_ENTRY.to_string()]).collect(),
+                validation,
+                get: Box::new(get),
+                set: Box::new(set),
+                format: Box::new(format),
+            },
+        }
+    }
+
+    /// 开关选项
+    pub fn toggle(
+        label: &'static str,
+        help: impl Into<String>,
+        (on, off): (&'static str, &'static str),
+        get: impl Fn(&C) -> bool + 'a,
+        set: impl Fn(&mut C, bool) + 'a,
+    ) -> Self {
+        Self {
+            label,
+            help: help.into(),
+            kind: FieldKind::Bool {
+                on,
+                off,
+                get: Box::new(get),
+                set: Box::new(set),
+            },
+        }
+    }
+
+    /// 当前取值的显示文字
+    fn value_text(&self, config: &C) -> String {
+        match &self.kind {
+            FieldKind::Enum { options, get, .. } => options[get(config)].clone(),
+            FieldKind::Range { get, format, .. } => format(config, get(config)),
+            FieldKind::Path { get, format, .. } => format(&get(config)),
+            FieldKind::Bool { on, off, get, .. } => {
+                if get(config) { on } else { off }.to_string()
+            }
+        }
+    }
+
+    /// 按←/→调整取值，路径选项可能打开文件浏览器
+    fn adjust(
+        &self,
+        config: &mut C,
+        forward: bool,
+        terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    ) -> Result<()> {
+        match &self.kind {
+            FieldKind::Enum { options, get, set } => {
+                let idx = get(config);
+                let idx = if forward {
+                    (idx + 1).min(options.len() - 1)
+                } else {
+                    idx.saturating_sub(1)
+                };
+                set(config, idx);
+            }
+            FieldKind::Range {
+                min,
+                max,
+                step,
+                get,
+                set,
+                ..
+            } => {
+                set(config, step_range(get(config), forward, (*min, *max, *step)));
+            }
+            FieldKind::Path {
+                candidates,
+                validation,
+                get,
+                set,
+                ..
+            } => {
+                let current = get(config);
+                let manual = candidates.len() - 1;
+                let current_idx = candidates
+                    .iter()
+                    .position(|f| *f == current)
+                    .unwrap_or(manual);
+                if !forward {
+                    // 从手动输入切换回列表时回到最后一个候选项，第一项再往左是手动输入
+                    let idx = match current_idx {
+                        i if i == manual => manual.saturating_sub(1),
+                        0 => manual,
+                        i => i - 1,
+                    };
+                    set(config, candidates[idx].clone());
+                } else if current_idx < manual {
+                    set(config, candidates[current_idx + 1].clone());
+                } else {
+                    let title = format!("选择{} (Enter确认, ESC取消)", validation.noun());
+                    let picker = FilePicker::new(&title, &current, *validation);
+                    match picker.run(terminal)? {
+                        Some(path) => set(config, path),
+                        // 取消时保持原来的选择，只有停在占位项上时才回到最后一个候选项
+                        None if current == MANUAL_ENTRY => {
+                            set(config, candidates[manual.saturating_sub(1)].clone())
+                        }
+                        None => {}
+                    }
+                }
+            }
+            FieldKind::Bool { set, .. } => set(config, forward),
+        }
+        Ok(())
+    }
+}
+
+/// 声明式设置表单：按字段列表统一渲染和处理按键
+pub struct SettingsForm<'a, C> {
+    title: &'static str,
+    fields: Vec<Field<'a, C>>,
+}
+
+impl<'a, C> SettingsForm<'a, C> {
+    pub fn new(title: &'static str, fields: Vec<Field<'a, C>>) -> Self {
+        Self { title, fields }
+    }
+
+    /// 运行表单直接修改config，Enter确认返回true，ESC取消返回false
+    pub fn run(
+        &self,
+        terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+        config: &mut C,
+    ) -> Result<bool> {
+        let mut selected_item = 0;
+        loop {
+            let items: Vec<ListItem> = self
+                .fields
+                .iter()
+                .map(|field| ListItem::new(format!("{}: {}", field.label, field.value_text(config))))
+                .collect();
+            let help = self.fields.get(selected_item).map_or("", |field| field.help.as_str());
+
+            terminal.draw(|f| {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .margin(1)
+                    .constraints([
+                        Constraint::Length(3), // 标题
+                        Constraint::Min(8),    // 设置选项
+                        Constraint::Length(4), // 选项说明
+                        Constraint::Length(3), // 操作提示
+                    ])
+                    .split(f.area());
+
+                let title = Paragraph::new(self.title)
+                    .block(Block::default().borders(Borders::ALL))
+                    .alignment(Alignment::Center);
+                f.render_widget(title, chunks[0]);
+
+                let mut state = ListState::default().with_selected(Some(selected_item));
+                let list = List::new(items)
+                    .block(Block::default().borders(Borders::ALL).title("设置选项"))
+                    .highlight_style(Style::default().bg(Color::Blue).fg(Color::White))
+                    .highlight_symbol(">> ");
+                f.render_stateful_widget(list, chunks[1], &mut state);
+
+                let help = Paragraph::new(help)
+                    .style(Style::default().fg(Color::Yellow))
+                    .wrap(Wrap { trim: true })
+                    .block(Block::default().borders(Borders::ALL).title("说明"));
+                f.render_widget(help, chunks[2]);
+
+                let keys =
+                    Paragraph::new("↑/↓: 选择选项 | ←/→: 修改选项 | Enter: 确认 | ESC: 取消")
+                        .block(Block::default().borders(Borders::ALL))
+                        .alignment(Alignment::Center);
+                f.render_widget(keys, chunks[3]);
+            })?;
+
+            if let Event::Key(key) = event::read()? {
+                #[cfg(windows)]
+                if key.kind != event::KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Up => selected_item = selected_item.saturating_sub(1),
+                    KeyCode::Down => {
+                        selected_item = (selected_item + 1).min(self.fields.len().saturating_sub(1))
+                    }
+                    KeyCode::Left | KeyCode::Right => {
+                        if let Some(field) = self.fields.get(selected_item) {
+                            field.adjust(config, key.code == KeyCode::Right, terminal)?;
+                        }
+                    }
+                    KeyCode::Enter => return Ok(true),
+                    KeyCode::Esc => return Ok(false),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_steps_stay_on_grid() {
+        let bounds = (0.5, 3.0, 0.1);
+        let mut value = 0.5;
+        for _ in 0..20 {
+            value = step_range(value, true, bounds);
+        }
+        assert_eq!(value, 0.5 + 20.0 * 0.1);
+        assert_eq!(format!("{}", value), "2.5");
+        for _ in 0..20 {
+            value = step_range(value, false, bounds);
+        }
+        assert_eq!(value, 0.5);
+    }
+
+    #[test]
+    fn range_steps_clamp_to_bounds() {
+        assert_eq!(step_range(3.0, true, (0.5, 3.0, 0.1)), 3.0);
+        assert_eq!(step_range(0.5, false, (0.5, 3.0, 0.1)), 0.5);
+        assert_eq!(step_range(5.0, false, (1.0, 10.0, 1.0)), 4.0);
+    }
+}
@@ -0,0 +1,757 @@
+use crate::game::{GameConfig, GameState, PracticeMode, Radical};
+use crate::leaderboard::Round;
+use crate::profile::{self, Profile};
+use crate::scheme::SchemeMeta;
+use crate::{show_confirm_dialog, show_message};
+use anyhow::{anyhow, bail, Context, Result};
+use crossterm::event::{self, Event, KeyCode};
+use rand::seq::SliceRandom;
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Style},
+    widgets::{Block, Borders, Gauge, Paragraph, Wrap},
+    Terminal,
+};
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+/// 默认端口
+pub const DEFAULT_PORT: u16 = 7878;
+/// 等待网络消息时刷新界面的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// 连接主机的每个地址的超时时间
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 联网对战中的角色
+pub enum Role {
+    Host {
+        port: u16,        // 监听端口
+        seconds: u64,     // 对战时限
+        questions: usize, // 题目数量
+    },
+    Join {
+        address: String, // 主机地址，如127.0.0.1:7878
+    },
+}
+
+/// 对战双方的座位
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Seat {
+    Host,
+    Guest,
+}
+
+/// 对战协议的消息，每条消息是一行JSON，type字段区分类型(协议说明见README)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Message {
+    /// 加入方连接后发送
+    Hello { name: String },
+    /// 主机发送题目后开始计时
+    Start {
+        name: String,          // 主机玩家名称
+        seconds: u64,          // 对战时限
+        mode: PracticeMode,    // 练习模式
+        scheme: String,        // 字根方案
+        radicals: Vec<String>, // 随机抽出的题目
+    },
+    /// 双方每次作答后发送
+    Progress {
+        answered: usize,
+        correct: usize,
+        wrong: usize,
+        score: usize,
+        finished: bool, // 是否已答对全部题目
+    },
+    /// 主机判定胜负后发送，winner为null表示平局；任一方退出时发送对方获胜的结果
+    Result { winner: Option<Seat>, reason: String },
+}
+
+impl Seat {
+    fn other(self) -> Self {
+        match self {
+            Seat::Host => Seat::Guest,
+            Seat::Guest => Seat::Host,
+        }
+    }
+}
+
+/// 一方的对战进度
+#[derive(Debug, Clone, Default)]
+struct Side {
+    name: String,
+    correct: usize, // 已答对的题数，答错时留在当前题目
+    score: usize,
+    finished: bool,
+}
+
+/// 按行收发JSON消息的连接，读取在后台线程中进行
+struct Connection {
+    writer: TcpStream,
+    incoming: Receiver<Message>,
+}
+
+impl Connection {
+    fn new(stream: TcpStream) -> Result<Self> {
+        let reader = BufReader::new(stream.try_clone()?);
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            for line in reader.lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                // 无法解析的行直接忽略，便于以后扩展协议
+                if let Ok(message) = serde_json::from_str(&line) {
+                    if tx.send(message).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        Ok(Self {
+            writer: stream,
+            incoming: rx,
+        })
+    }
+
+    fn send(&mut self, message: &Message) -> Result<()> {
+        let mut line = serde_json::to_string(message)?;
+        line.push('\n');
+        self.writer
+            .write_all(line.as_bytes())
+            .context("对方已断开连接")
+    }
+
+    /// 取出一条收到的消息，对方断开时返回错误
+    fn try_recv(&self) -> Result<Option<Message>> {
+        match self.incoming.try_recv() {
+            Ok(message) => Ok(Some(message)),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err(anyhow!("对方已断开连接")),
+        }
+    }
+}
+
+/// 联网对战入口：选择练习设置后作为主机等待连接或加入主机
+pub fn run(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    role: Role,
+    profile: &Profile,
+) -> Result<()> {
+    let mut config = GameConfig::show_settings_menu(terminal)?;
+    if config.cancelled {
+        return Ok(());
+    }
+    let radicals = Radical::load_from_files(&config.frequency_file, &config.radical_file)?;
+    let result = match role {
+        Role::Host {
+            port,
+            seconds,
+            questions,
+        } => host(terminal, &config, radicals, profile, port, seconds, questions),
+        Role::Join { address } => join(terminal, &mut config, radicals, profile, &address),
+    };
+    // 网络错误提示后返回，不直接退出程序
+    if let Err(e) = result {
+        show_message(terminal, &format!("联网对战结束: {:#}", e))?;
+    }
+    Ok(())
+}
+
+/// 在等待界面上反复调用poll，直到得到结果；ESC取消时返回None
+fn wait_for<T>(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    status: &str,
+    mut poll: impl FnMut() -> Result<Option<T>>,
+) -> Result<Option<T>> {
+    loop {
+        if let Some(value) = poll()? {
+            return Ok(Some(value));
+        }
+        terminal.draw(|f| {
+            let text = Paragraph::new(format!("{}\n\nESC: 取消", status))
+                .block(Block::default().title("联网对战").borders(Borders::ALL))
+                .alignment(Alignment::Center);
+            f.render_widget(text, f.area());
+        })?;
+        if event::poll(POLL_INTERVAL)? {
+            if let Event::Key(key) = event::read()? {
+                #[cfg(windows)]
+                if key.kind != event::KeyEventKind::Press {
+                    continue;
+                }
+                if key.code == KeyCode::Esc {
+                    return Ok(None);
+                }
+            }
+        }
+    }
+}
+
+/// 随机打乱字根并取前count个
+fn random_sequence(len: usize, count: usize) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..len).collect();
+    indices.shuffle(&mut rand::rng());
+    indices.truncate(count);
+    indices
+}
+
+fn host(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    config: &GameConfig,
+    radicals: Vec<Radical>,
+    profile: &Profile,
+    port: u16,
+    seconds: u64,
+    questions: usize,
+) -> Result<()> {
+    let listener =
+        TcpListener::bind(("0.0.0.0", port)).with_context(|| format!("无法监听端口{}", port))?;
+    listener.set_nonblocking(true)?;
+    let status = format!("等待对手连接，端口: {}", port);
+    let stream = wait_for(terminal, &status, || match listener.accept() {
+        Ok((stream, _)) => Ok(Some(stream)),
+        Err(e) if e.kind() == io::ErrorKind::WouldBlock => Ok(None),
+        Err(e) => Err(e.into()),
+    })?;
+    let Some(stream) = stream else {
+        return Ok(());
+    };
+    stream.set_nonblocking(false)?;
+    let mut conn = Connection::new(stream)?;
+
+    let opponent = wait_for(terminal, "已连接，等待对手报名", || {
+        Ok(match conn.try_recv()? {
+            Some(Message::Hello { name }) => Some(name),
+            _ => None,
+        })
+    })?;
+    let Some(opponent) = opponent else {
+        return Ok(());
+    };
+
+    let sequence = random_sequence(radicals.len(), questions);
+    if sequence.is_empty() {
+        bail!("没有可以出题的字根");
+    }
+    let meta = SchemeMeta::read_from_file(&config.radical_file);
+    conn.send(&Message::Start {
+        name: profile.name.clone(),
+        seconds,
+        mode: config.practice_mode,
+        scheme: profile::scheme_key(&config.radical_file, meta.as_ref()),
+        radicals: sequence.iter().map(|&i| radicals[i].text.clone()).collect(),
+    })?;
+
+    let mut game = Match {
+        config,
+        conn,
+        seat: Seat::Host,
+        sequence,
+        seconds,
+        me: Side {
+            name: profile.name.clone(),
+            ..Side::default()
+        },
+        opponent: Side {
+            name: opponent,
+            ..Side::default()
+        },
+    };
+    game.run(terminal, GameState::new(radicals, config))
+}
+
+/// 依次尝试连接主机地址解析出的每个地址
+fn connect(address: &str) -> Result<TcpStream> {
+    let addrs = address
+        .to_socket_addrs()
+        .with_context(|| format!("无法解析地址{}", address))?;
+    let mut last_error = anyhow!("地址{}没有解析出任何结果", address);
+    for addr in addrs {
+        match TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_error = anyhow!(e),
+        }
+    }
+    Err(last_error.context(format!("无法连接到{}", address)))
+}
+
+fn join(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    config: &mut GameConfig,
+    radicals: Vec<Radical>,
+    profile: &Profile,
+    address: &str,
+) -> Result<()> {
+    // 在后台线程中连接，连接期间界面仍可按ESC取消
+    let (tx, rx) = mpsc::channel();
+    let target = address.to_string();
+    thread::spawn(move || {
+        let _ = tx.send(connect(&target));
+    });
+    let status = format!("正在连接{}", address);
+    let stream = wait_for(terminal, &status, || match rx.try_recv() {
+        Ok(stream) => stream.map(Some),
+        Err(_) => Ok(None),
+    })?;
+    let Some(stream) = stream else {
+        return Ok(());
+    };
+    let mut conn = Connection::new(stream)?;
+    conn.send(&Message::Hello {
+        name: profile.name.clone(),
+    })?;
+
+    let start = wait_for(terminal, "已连接，等待主机开始", || {
+        Ok(match conn.try_recv()? {
+            Some(Message::Start {
+                name,
+                seconds,
+                mode,
+                scheme,
+                radicals,
+            }) => Some((name, seconds, mode, scheme, radicals)),
+            _ => None,
+        })
+    })?;
+    let Some((opponent, seconds, mode, scheme, texts)) = start else {
+        return Ok(());
+    };
+    let meta = SchemeMeta::read_from_file(&config.radical_file);
+    let local_scheme = profile::scheme_key(&config.radical_file, meta.as_ref());
+    if scheme != local_scheme {
+        bail!(
+            "双方的字根方案不一致：主机使用“{}”，本方使用“{}”，请在设置中选择相同的方案",
+            scheme,
+            local_scheme
+        );
+    }
+
+    // 题目按字根查找，双方需要使用同一个字根方案
+    let sequence = texts
+        .iter()
+        .map(|text| {
+            radicals
+                .iter()
+                .position(|r| r.text == *text)
+                .ok_or_else(|| anyhow!("双方的字根方案不一致，本方没有字根“{}”", text))
+        })
+        .collect::<Result<Vec<usize>>>()?;
+    if sequence.is_empty() {
+        bail!("主机没有发送题目");
+    }
+    config.practice_mode = mode;
+
+    let mut game = Match {
+        config,
+        conn,
+        seat: Seat::Guest,
+        sequence,
+        seconds,
+        me: Side {
+            name: profile.name.clone(),
+            ..Side::default()
+        },
+        opponent: Side {
+            name: opponent,
+            ..Side::default()
+        },
+    };
+    game.run(terminal, GameState::new(radicals, config))
+}
+
+/// 判定胜负：先答对全部题目的一方获胜，时间到或双方同时答完时比较得分，还没有结果时返回None
+fn decide(host: &Side, guest: &Side, time_up: bool) -> Option<(Option<Seat>, String)> {
+    let by_score = |reason: &str| {
+        let winner = match host.score.cmp(&guest.score) {
+            std::cmp::Ordering::Greater => Some(Seat::Host),
+            std::cmp::Ordering::Less => Some(Seat::Guest),
+            std::cmp::Ordering::Equal => None,
+        };
+        Some((winner, reason.to_string()))
+    };
+    match (host.finished, guest.finished) {
+        (true, true) => by_score("双方都已答完，按得分判定"),
+        (true, false) => Some((Some(Seat::Host), "先答对全部题目".to_string())),
+        (false, true) => Some((Some(Seat::Guest), "先答对全部题目".to_string())),
+        (false, false) if time_up => by_score("时间到，按得分判定"),
+        _ => None,
+    }
+}
+
+/// 一场对战
+struct Match<'a> {
+    config: &'a GameConfig,
+    conn: Connection,
+    seat: Seat,           // 本方座位，主机负责判定胜负
+    sequence: Vec<usize>, // 题目(字根索引)
+    seconds: u64,         // 对战时限
+    me: Side,
+    opponent: Side,
+}
+
+impl Match<'_> {
+    /// 主机判定胜负
+    fn decide(&self, round: &Round) -> Option<(Option<Seat>, String)> {
+        decide(&self.me, &self.opponent, round.is_over())
+    }
+
+    fn progress(&self, round: &Round) -> Message {
+        Message::Progress {
+            answered: round.answers,
+            correct: round.correct,
+            wrong: round.wrong,
+            score: round.score(),
+            finished: self.me.finished,
+        }
+    }
+
+    fn run(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+        mut state: GameState,
+    ) -> Result<()> {
+        let config = self.config;
+        let mut round = Round::new(self.seconds);
+        let mut current = 0;
+        let mut input = String::new();
+        let mut feedback: Option<String> = None;
+        state.show_radical(self.sequence[0], config);
+
+        // 对方断开连接时留下的一方获胜
+        let disconnected = || (Some(self.seat), "对方断开了连接".to_string());
+        let (winner, reason) = 'game: loop {
+            loop {
+                let message = match self.conn.try_recv() {
+                    Ok(Some(message)) => message,
+                    Ok(None) => break,
+                    Err(_) => break 'game disconnected(),
+                };
+                match message {
+                    Message::Progress {
+                        correct,
+                        score,
+                        finished,
+                        ..
+                    } => {
+                        self.opponent.correct = correct;
+                        self.opponent.score = score;
+                        self.opponent.finished = finished;
+                    }
+                    // 加入方只接受主机判定的结果，主机只接受对方认输
+                    Message::Result { winner, reason }
+                        if self.seat == Seat::Guest || winner == Some(self.seat) =>
+                    {
+                        break 'game (winner, reason);
+                    }
+                    _ => {}
+                }
+            }
+            if self.seat == Seat::Host {
+                if let Some((winner, reason)) = self.decide(&round) {
+                    // 结果已经判定，对方此时断开也不影响
+                    let _ = self.conn.send(&Message::Result {
+                        winner,
+                        reason: reason.clone(),
+                    });
+                    break (winner, reason);
+                }
+            }
+
+            let playing = !self.me.finished && !round.is_over();
+            self.draw(terminal, &state, &round, playing, &input, feedback.as_deref())?;
+
+            if !event::poll(POLL_INTERVAL)? {
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            #[cfg(windows)]
+            if key.kind != event::KeyEventKind::Press {
+                continue;
+            }
+            let submit = match key.code {
+                KeyCode::Esc => {
+                    if show_confirm_dialog(terminal, "退出对战？(对方将获胜)")? {
+                        // 退出前告知对方获胜，对方已断开时忽略
+                        let _ = self.conn.send(&Message::Result {
+                            winner: Some(self.seat.other()),
+                            reason: format!("{}退出了对战", self.me.name),
+                        });
+                        return Ok(());
+                    }
+                    false
+                }
+                _ if !playing => false,
+                KeyCode::Char(c) if config.practice_mode.is_choice() => {
+                    input = c.to_string();
+                    true
+                }
+                KeyCode::Char(c) => {
+                    input.push(c);
+                    // 逐键反馈模式下输满编码长度自动提交
+                    state.input_complete(&input, config)
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                    false
+                }
+                KeyCode::Enter => true,
+                _ => false,
+            };
+            if !submit {
+                continue;
+            }
+
+            let answered = state.total_practice;
+            let is_correct = state.check_input(&input, config);
+            input.clear();
+            feedback = state.last_error.take();
+            // 无效输入(如空输入)不算作答，留在当前题目
+            if state.total_practice == answered {
+                continue;
+            }
+            let latency_ms = state.recent_results.back().and_then(|r| r.latency_ms);
+            round.record(Some(is_correct), latency_ms);
+            // 答对才进入下一题，答完全部题目必须每题都答对
+            if is_correct {
+                current += 1;
+                match self.sequence.get(current) {
+                    Some(&idx) => state.show_radical(idx, config),
+                    None => self.me.finished = true,
+                }
+            }
+            self.me.correct = current;
+            self.me.score = round.score();
+            let progress = self.progress(&round);
+            if self.conn.send(&progress).is_err() {
+                break disconnected();
+            }
+        };
+
+        let outcome = match winner {
+            Some(seat) if seat == self.seat => "你赢了!",
+            Some(_) => "对手获胜",
+            None => "平局",
+        };
+        show_message(
+            terminal,
+            &format!(
+                "{} ({}) | {}: {}分 | {}: {}分",
+                outcome,
+                reason,
+                self.me.name,
+                self.me.score,
+                self.opponent.name,
+                self.opponent.score
+            ),
+        )
+    }
+
+    fn draw(
+        &self,
+        terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+        state: &GameState,
+        round: &Round,
+        playing: bool,
+        input: &str,
+        feedback: Option<&str>,
+    ) -> Result<()> {
+        let config = self.config;
+        let total = self.sequence.len();
+        terminal.draw(|f| {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .margin(1)
+                .constraints([
+                    Constraint::Length(3), // 本方进度
+                    Constraint::Length(3), // 对方进度
+                    Constraint::Length(3), // 当前字根
+                    Constraint::Length(3), // 输入框
+                    Constraint::Min(4),    // 上一题的反馈
+                    Constraint::Length(3), // 操作提示
+                ])
+                .split(f.area());
+
+            for (i, (side, title, color)) in [
+                (&self.me, "你", Color::Green),
+                (&self.opponent, "对手", Color::Red),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                let status = if side.finished { " (已答完)" } else { "" };
+                let gauge = Gauge::default()
+                    .block(
+                        Block::default()
+                            .title(format!("{}: {}", title, side.name))
+                            .borders(Borders::ALL),
+                    )
+                    .gauge_style(Style::default().fg(color))
+                    .ratio((side.correct as f64 / total as f64).min(1.0))
+                    .label(format!(
+                        "{}/{} | 得分: {}{}",
+                        side.correct, total, side.score, status
+                    ));
+                f.render_widget(gauge, chunks[i]);
+            }
+
+            if playing {
+                if let Some((title, prompt)) = state.prompt(config) {
+                    let prompt = Paragraph::new(prompt)
+                        .block(Block::default().title(title).borders(Borders::ALL))
+                        .alignment(Alignment::Center);
+                    f.render_widget(prompt, chunks[2]);
+                }
+                let (title, content) = if config.practice_mode.is_choice() {
+                    ("选择答案 (按1-4)", state.choice_labels(config).join("    "))
+                } else {
+                    ("输入编码 (Enter确认)", input.to_string())
+                };
+                let input_box = Paragraph::new(content)
+                    .block(Block::default().title(title).borders(Borders::ALL))
+                    .alignment(Alignment::Center);
+                f.render_widget(input_box, chunks[3]);
+            } else {
+                let waiting = Paragraph::new("等待对战结果...")
+                    .block(Block::default().borders(Borders::ALL))
+                    .alignment(Alignment::Center);
+                f.render_widget(waiting, chunks[2]);
+            }
+
+            if let Some(message) = feedback {
+                let color = if message.starts_with("【正确】") {
+                    Color::Green
+                } else {
+                    Color::Red
+                };
+                let message = Paragraph::new(message)
+                    .style(Style::default().fg(color))
+                    .wrap(Wrap { trim: true })
+                    .block(Block::default().title("上一题").borders(Borders::ALL))
+                    .alignment(Alignment::Center);
+                f.render_widget(message, chunks[4]);
+            }
+
+            let help = Paragraph::new(format!(
+                "剩余: {}秒 | 答对才进入下一题，先答完全部题目获胜，时间到按得分判定 | ESC: 退出",
+                round.remaining().as_secs()
+            ))
+            .block(Block::default().borders(Borders::ALL))
+            .alignment(Alignment::Center);
+            f.render_widget(help, chunks[5]);
+        })?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn side(score: usize, finished: bool) -> Side {
+        Side {
+            score,
+            finished,
+            ..Side::default()
+        }
+    }
+
+    #[test]
+    fn messages_match_the_documented_protocol() {
+        let messages = [
+            (
+                Message::Hello {
+                    name: "小明".to_string(),
+                },
+                r#"{"type":"hello","name":"小明"}"#,
+            ),
+            (
+                Message::Start {
+                    name: "小红".to_string(),
+                    seconds: 120,
+                    mode: PracticeMode::DualCode,
+                    scheme: "yujoy".to_string(),
+                    radicals: vec!["甫".to_string(), "牙".to_string()],
+                },
+                r#"{"type":"start","name":"小红","seconds":120,"mode":"DualCode","scheme":"yujoy","radicals":["甫","牙"]}"#,
+            ),
+            (
+                Message::Progress {
+                    answered: 5,
+                    correct: 4,
+                    wrong: 1,
+                    score: 3,
+                    finished: false,
+                },
+                r#"{"type":"progress","answered":5,"correct":4,"wrong":1,"score":3,"finished":false}"#,
+            ),
+            (
+                Message::Result {
+                    winner: Some(Seat::Guest),
+                    reason: "先答对全部题目".to_string(),
+                },
+                r#"{"type":"result","winner":"guest","reason":"先答对全部题目"}"#,
+            ),
+            (
+                Message::Result {
+                    winner: None,
+                    reason: "时间到，按得分判定".to_string(),
+                },
+                r#"{"type":"result","winner":null,"reason":"时间到，按得分判定"}"#,
+            ),
+        ];
+        for (message, line) in messages {
+            assert_eq!(serde_json::to_string(&message).unwrap(), line);
+            let parsed: Message = serde_json::from_str(line).unwrap();
+            assert_eq!(serde_json::to_string(&parsed).unwrap(), line);
+        }
+
+        // 练习模式使用枚举名
+        for mode in PracticeMode::ALL {
+            let json = serde_json::to_string(&mode).unwrap();
+            assert_eq!(json, format!("\"{:?}\"", mode));
+        }
+    }
+
+    #[test]
+    fn decide_first_to_finish_wins() {
+        let result = decide(&side(3, true), &side(9, false), false);
+        assert_eq!(
+            result,
+            Some((Some(Seat::Host), "先答对全部题目".to_string()))
+        );
+        let result = decide(&side(9, false), &side(3, true), true);
+        assert_eq!(
+            result,
+            Some((Some(Seat::Guest), "先答对全部题目".to_string()))
+        );
+        assert_eq!(decide(&side(9, false), &side(3, false), false), None);
+    }
+
+    #[test]
+    fn decide_by_score_when_both_finish_or_time_is_up() {
+        let reason = "双方都已答完，按得分判定".to_string();
+        let result = decide(&side(5, true), &side(7, true), false);
+        assert_eq!(result, Some((Some(Seat::Guest), reason.clone())));
+        assert_eq!(
+            decide(&side(7, true), &side(7, true), false),
+            Some((None, reason))
+        );
+
+        let reason = "时间到，按得分判定".to_string();
+        let result = decide(&side(8, false), &side(7, false), true);
+        assert_eq!(result, Some((Some(Seat::Host), reason.clone())));
+        assert_eq!(
+            decide(&side(7, false), &side(7, false), true),
+            Some((None, reason))
+        );
+    }
+}